@@ -0,0 +1,22 @@
+-- Splits the attack into smaller projectiles once it stops
+local split = {}
+
+local PIECES = 3
+local SPREAD = 0.4
+
+function split:on_spawn()
+	self.angle = math.atan2(self.target.y - self.pos.y, self.target.x - self.pos.x)
+end
+
+function split:on_expire()
+	for i = 1, PIECES do
+		local angle = self.angle + math.pi + (i - (PIECES + 1) / 2) * SPREAD
+		local target = { x = self.pos.x + math.cos(angle), y = self.pos.y + math.sin(angle) }
+
+		attack.spawn(
+			attack.projectile(self.damage / 2, self.size / 2, self.pos, target, "default:attacks/projectile-player", self.owner)
+		)
+	end
+end
+
+return split
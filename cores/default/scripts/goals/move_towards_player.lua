@@ -10,7 +10,17 @@ end
 
 function mtp:update(pos_self, pos_player)
 	self.timeout -= engine.delta_time() * 60
-	return move_towards(pos_self, pos_player, 1.2)
+
+	-- Walking around walls, rather than into them
+	local target = pos_player
+	if not engine.has_line_of_sight(pos_self, pos_player) then
+		local path = engine.find_path(pos_self, pos_player, self.size)
+		if path then
+			target = path[1]
+		end
+	end
+
+	return move_towards(pos_self, target, 1.2)
 end
 
 function mtp:should_stop()
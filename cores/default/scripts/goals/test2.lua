@@ -5,6 +5,11 @@ function test2:should_start(pos_self, pos_player, prev_goal)
 		return false
 	end
 
+	-- Not throwing projectiles into walls
+	if not engine.has_line_of_sight(pos_self, pos_player) then
+		return false
+	end
+
 	local dist = distance_between(pos_self, pos_player)
 	return math.round(dist) % 2 == 0
 end
@@ -22,6 +27,10 @@ function test2:update(pos_self, pos_player, anim)
 
 	if self.countdown <= 0 then
 		attack.spawn(attack.projectile(12, 10, pos_self, pos_player, "default:attacks/projectile-enemy"))
+
+		-- Recoil from throwing the projectile
+		local towards = move_towards(pos_self, pos_player, 20)
+		return pos_self, { x = pos_self.x - towards.x, y = pos_self.y - towards.y }
 	end
 
 	return pos_self
@@ -15,12 +15,18 @@ use crate::utils::{
 };
 
 pub mod audio;
+pub mod damagetype;
+pub mod effecttype;
 pub mod enemytype;
+pub mod itemtype;
 pub mod lang;
 pub mod map;
 pub mod npctype;
+pub mod parrytype;
 pub mod script;
+pub mod temperaturetype;
 pub mod textures;
+pub mod weapontype;
 
 const DIR_SPLIT: &[char] = &['/', '\\', '.'];
 const CORES_DIR: &str = "./cores"; // TODO: Make Configurable 
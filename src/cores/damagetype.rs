@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::prelude::*;
+
+/// A kind of damage that attacks can deal, which entities can resist or be weak to
+#[derive(Clone, Deserialize)]
+pub struct DamageType {
+	/// How hard damage of this type pushes what it hits
+	pub knockback: f64,
+	/// How much temperature is gained per point of damage of this type taken
+	#[serde(default)]
+	pub heat: f64,
+}
+
+/// Provides a `HashMap` containing all `DamageTypes`
+pub fn get_damagetypes() -> FxHashMap<String, DamageType> {
+	let damagetypes: FxHashMap<String, DamageType> = get_files("damage")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("DamageType {str} failed to load: {e}");
+				None
+			}
+			Ok(damagetype) => {
+				info!("DamageType {str} loaded!");
+				Some((str, damagetype))
+			}
+		})
+		.collect();
+
+	damagetypes
+}
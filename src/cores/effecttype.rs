@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::prelude::*;
+
+/// A struct containing the stats of a status effect, which attacks can apply to what they hit.
+///
+/// All of an effect's stats are applied once for each of its stacks.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EffectType {
+	pub duration: f64,
+	pub stacking: Stacking,
+	pub tint: (u8, u8, u8),
+
+	/// How much damage is taken over each unit of time
+	#[serde(default)]
+	pub damage: f64,
+	/// The type of the damage taken over time, if it should be affected by resistances
+	#[serde(default)]
+	pub damage_type: Option<String>,
+	/// Multiplies how fast the affected entity moves
+	#[serde(default = "one")]
+	pub speed: f64,
+	/// Multiplies all damage the affected entity takes
+	#[serde(default = "one")]
+	pub damage_taken: f64,
+	/// If true, the affected entity is stunned for as long as the effect lasts
+	#[serde(default)]
+	pub stuns: bool,
+}
+
+/// What happens when an effect is applied to something that already has it
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Stacking {
+	/// The duration is reset
+	Refresh,
+	/// The duration is added onto what remains
+	Extend,
+	/// Another stack is added, up to the provided maximum, and the duration is reset
+	Stack(u32),
+	/// Nothing happens
+	Ignore,
+}
+
+fn one() -> f64 {
+	1.
+}
+
+/// Provides a `HashMap` containing all `EffectTypes`
+pub fn get_effecttypes() -> FxHashMap<String, EffectType> {
+	let effecttypes: FxHashMap<String, EffectType> = get_files("effects")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path::<EffectType>(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("EffectType {str} failed to load: {e}");
+				None
+			}
+			Ok(effecttype) => {
+				info!("EffectType {str} loaded!");
+				Some((str, effecttype))
+			}
+		})
+		.collect();
+
+	effecttypes
+}
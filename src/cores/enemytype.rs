@@ -3,29 +3,68 @@ use tracing::warn;
 
 use super::{gen_name, get_files, read_from_path};
 
-use crate::{gameplay::ecs::sprite::Frames, prelude::*, utils::ImmutVec};
+use crate::{
+	gameplay::ecs::{obj::hitbox::Hitbox, sprite::Frames},
+	prelude::*,
+	utils::{ImmutVec, resources::damage::access_damage_type},
+};
 
 /// A struct containing the stats of an enemy type
 #[derive(Clone, Deserialize)]
 pub struct EnemyType {
 	pub max_health: f64,
 	pub size: f64,
+	/// The shape used to check if attacks hit the enemy
+	#[serde(default)]
+	pub hitbox: Hitbox,
+	/// The mass used when colliding with other entities, if the enemy should collide with them
+	#[serde(default)]
+	pub mass: Option<f64>,
 	pub sprite: String,
+	/// How much damage of each type is multiplied by, such as 0.5 to resist it or 2 to be weak to it
+	#[serde(default)]
+	pub resistances: FxHashMap<String, f64>,
 	pub goals: ImmutVec<String>,
+	/// The enemy's animations, where `death` is played once it dies
 	pub anims: FxHashMap<String, Frames>,
+	/// The items that may be dropped when the enemy dies
+	#[serde(default)]
+	pub drops: ImmutVec<EnemyDrop>,
+	/// One of these is played at random when the enemy dies
+	#[serde(default)]
+	pub death_sounds: ImmutVec<String>,
+}
+
+/// An item that an enemy may drop when it dies
+#[derive(Clone, Deserialize)]
+pub struct EnemyDrop {
+	/// The key of the item's [`ItemType`](super::itemtype::ItemType)
+	pub item: String,
+	/// The chance of the item being dropped, from `0` to `1`
+	#[serde(default = "one")]
+	pub chance: f64,
+}
+
+fn one() -> f64 {
+	1.
 }
 
 /// Provides a `HashMap` containing all `EnemyTypes`
 pub fn get_enemytypes() -> FxHashMap<String, EnemyType> {
 	let enemytypes: FxHashMap<String, EnemyType> = get_files("enemies")
 		.iter()
-		.map(|dir| (gen_name(dir), read_from_path(dir)))
+		.map(|dir| (gen_name(dir), read_from_path::<EnemyType>(dir)))
 		.filter_map(|(str, result)| match result {
 			Err(e) => {
 				warn!("EnemyType {str} failed to load: {e}");
 				None
 			}
 			Ok(enemytype) => {
+				// Logs an error for any damage types that do not exist
+				for key in enemytype.resistances.keys() {
+					access_damage_type(key);
+				}
+
 				info!("EnemyType {str} loaded!");
 				Some((str, enemytype))
 			}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::prelude::*;
+
+/// A struct containing the stats of an item that can be picked up
+#[derive(Clone, Deserialize)]
+pub struct ItemType {
+	pub sprite: String,
+	pub radius: f64,
+	pub effect: ItemEffect,
+
+	/// If true, the item stays collected once picked up, rather than coming back whenever the map is entered.
+	/// Only items that are fine to collect repeatedly, such as healing, should leave this off.
+	/// Items dropped by enemies never stay collected, as they aren't part of the map.
+	#[serde(default)]
+	pub once: bool,
+}
+
+/// What happens when an item is picked up
+#[derive(Clone, Deserialize)]
+pub enum ItemEffect {
+	/// Restores the provided amount of health
+	Heal(f64),
+	/// Gives the provided amount of currency
+	Currency(u64),
+	/// Unlocks the weapon with the provided key
+	Weapon(String),
+	/// Increases the player's max health by the provided amount
+	MaxHealth(f64),
+}
+
+/// Provides a `HashMap` containing all `ItemTypes`
+pub fn get_itemtypes() -> FxHashMap<String, ItemType> {
+	let itemtypes: FxHashMap<String, ItemType> = get_files("items")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("ItemType {str} failed to load: {e}");
+				None
+			}
+			Ok(itemtype) => {
+				info!("ItemType {str} loaded!");
+				Some((str, itemtype))
+			}
+		})
+		.collect();
+
+	itemtypes
+}
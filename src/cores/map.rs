@@ -1,27 +1,46 @@
+use std::sync::OnceLock;
+
+use grid::{BarrierGrid, Segment, SegmentKind};
+use nav::NavGrid;
 use raywoke::Barrier;
 use serde::Deserialize;
 
 use super::{
 	enemytype::{EnemyType, get_enemytypes},
 	gen_name, get_files,
+	itemtype::{ItemType, get_itemtypes},
 	npctype::{NpcType, get_npctypes},
 	read_from_path,
 };
 
 use crate::{
-	gameplay::{doors::Door, draw::process::to_texture},
+	gameplay::{
+		arena::{Arena, ArenaBuilder},
+		breakable::{BreakableBuilder, BreakableType},
+		doors::Door,
+		draw::process::to_texture,
+	},
 	prelude::*,
 	utils::{ImmutVec, resources::textures::access_image, tup_vec::Tup64},
 };
 
 use imageproc::image::{DynamicImage, GenericImage};
 
+pub mod grid;
+pub mod nav;
+
 #[derive(Deserialize)]
 struct MapBuilder {
 	walls: Vec<Vec<DVec2>>,
 	doors: Vec<Door>,
 	enemies: Vec<(String, DVec2)>,
 	npcs: Vec<(String, DVec2)>,
+	#[serde(default)]
+	breakables: Vec<BreakableBuilder>,
+	#[serde(default)]
+	pickups: Vec<(String, DVec2)>,
+	#[serde(default)]
+	arenas: Vec<ArenaBuilder>,
 	tilemap: MapTexture,
 }
 
@@ -35,9 +54,16 @@ struct MapTexture {
 pub struct Map {
 	pub walls: ImmutVec<ImmutVec<Barrier>>,
 	pub doors: ImmutVec<Door>,
+	pub breakables: ImmutVec<BreakableType>,
 	pub enemies: ImmutVec<(EnemyType, DVec2)>,
 	pub npcs: ImmutVec<(NpcType, DVec2)>,
-	pub texture: Texture2D,
+	pub pickups: ImmutVec<(ItemType, DVec2)>,
+	pub arenas: ImmutVec<Arena>,
+	pub grid: BarrierGrid,
+	pub nav: NavGrid,
+	pub image: DynamicImage,
+	/// Created from the image the first time it is needed, so that maps can be loaded without a window
+	pub texture: OnceLock<Texture2D>,
 }
 
 impl MapBuilder {
@@ -45,6 +71,7 @@ impl MapBuilder {
 		self,
 		enemytypes: &FxHashMap<String, EnemyType>,
 		npctypes: &FxHashMap<String, NpcType>,
+		itemtypes: &FxHashMap<String, ItemType>,
 	) -> Map {
 		// Handles the iterator chain for enemies/npcs
 		fn iter_thing<T: Clone>(
@@ -66,41 +93,90 @@ impl MapBuilder {
 				.collect()
 		}
 
-		Map {
-			walls: {
-				let mut walls = vec![];
+		let walls: ImmutVec<ImmutVec<Barrier>> = {
+			let mut walls = vec![];
 
-				for wall in self.walls {
-					let bar = |start: usize, end: usize| {
-						Barrier::new(wall[start].tup64(), wall[end].tup64())
-					};
+			for wall in self.walls {
+				let bar =
+					|start: usize, end: usize| Barrier::new(wall[start].tup64(), wall[end].tup64());
 
-					let mut vec = vec![];
+				let mut vec = vec![];
 
-					for point in 0..wall.len() {
-						match wall.get(point + 1) {
-							Some(_) => vec.push(bar(point, point + 1)),
-							None => vec.push(bar(point, 0)),
-						}
+				for point in 0..wall.len() {
+					match wall.get(point + 1) {
+						Some(_) => vec.push(bar(point, point + 1)),
+						None => vec.push(bar(point, 0)),
 					}
-
-					walls.push(vec.into_boxed_slice());
 				}
 
-				walls.into_boxed_slice()
-			},
-			doors: self.doors.into_boxed_slice(),
+				walls.push(vec.into_boxed_slice());
+			}
+
+			walls.into_boxed_slice()
+		};
+		let doors = self.doors.into_boxed_slice();
+		let breakables: ImmutVec<BreakableType> = self
+			.breakables
+			.into_iter()
+			.map(BreakableBuilder::build)
+			.collect();
+
+		Map {
+			grid: build_grid(&walls, &doors, &breakables),
+			nav: NavGrid::new(&walls),
+			walls,
+			doors,
+			breakables,
 
 			enemies: iter_thing(&self.enemies, enemytypes, "EnemyType"),
 			npcs: iter_thing(&self.npcs, npctypes, "NpcType"),
+			pickups: iter_thing(&self.pickups, itemtypes, "ItemType"),
+			arenas: self
+				.arenas
+				.into_iter()
+				.map(|arena| arena.build(enemytypes))
+				.collect(),
+
+			image: self.tilemap.to_image(),
+			texture: OnceLock::new(),
+		}
+	}
+}
 
-			texture: self.tilemap.to_texture(),
+/// Places every barrier within the map into a [`BarrierGrid`]
+fn build_grid(
+	walls: &[ImmutVec<Barrier>],
+	doors: &[Door],
+	breakables: &[BreakableType],
+) -> BarrierGrid {
+	let mut segments = vec![];
+
+	for bar in walls.iter().flatten() {
+		segments.push(Segment {
+			bar: bar.clone(),
+			kind: SegmentKind::Wall,
+		});
+	}
+	for door in doors {
+		segments.push(Segment {
+			bar: door.to_barrier(),
+			kind: SegmentKind::Door,
+		});
+	}
+	for (index, breakable) in breakables.iter().enumerate() {
+		for bar in &breakable.barriers {
+			segments.push(Segment {
+				bar: bar.clone(),
+				kind: SegmentKind::Breakable(index),
+			});
 		}
 	}
+
+	BarrierGrid::new(segments)
 }
 
 impl MapTexture {
-	fn to_texture(&self) -> Texture2D {
+	fn to_image(&self) -> DynamicImage {
 		let mut texture = DynamicImage::new_rgba8(
 			(self.tiles[0].len() * 16) as u32,
 			(self.tiles.len() * 16) as u32,
@@ -120,7 +196,14 @@ impl MapTexture {
 			}
 		}
 
-		to_texture(&texture)
+		texture
+	}
+}
+
+impl Map {
+	/// Gets the texture of the map, creating it if it does not exist yet
+	pub fn texture(&self) -> &Texture2D {
+		self.texture.get_or_init(|| to_texture(&self.image))
 	}
 }
 
@@ -128,6 +211,7 @@ impl MapTexture {
 pub fn get_maps() -> FxHashMap<String, Map> {
 	let enemytypes = get_enemytypes();
 	let npctypes = get_npctypes();
+	let itemtypes = get_itemtypes();
 
 	get_files("maps")
 		.iter()
@@ -139,7 +223,7 @@ pub fn get_maps() -> FxHashMap<String, Map> {
 			}
 			Ok(map) => {
 				info!("Map {str} loaded!");
-				Some((str, map.build(&enemytypes, &npctypes)))
+				Some((str, map.build(&enemytypes, &npctypes, &itemtypes)))
 			}
 		})
 		.collect()
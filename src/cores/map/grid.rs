@@ -0,0 +1,156 @@
+use macroquad::math::{DVec2, dvec2};
+use raywoke::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::{
+	gameplay::{
+		breakable::{Broken, is_broken},
+		ecs::obj::collision::intersect_ray,
+	},
+	utils::{ImmutVec, tup_vec::DV2},
+};
+
+/// The width and height of each cell in the grid
+const CELL_SIZE: f64 = 64.;
+
+/// What a segment within the grid belongs to
+#[derive(Clone, Copy, PartialEq)]
+pub enum SegmentKind {
+	Wall,
+	Door,
+	/// A breakable, alongside its index within the map
+	Breakable(usize),
+}
+
+#[derive(Clone)]
+pub struct Segment {
+	pub bar: Barrier,
+	pub kind: SegmentKind,
+}
+
+/// Where a raycast hit a barrier
+pub struct RaycastHit {
+	pub pos: DVec2,
+	/// The normal of the barrier that was hit, facing towards the start of the ray
+	pub normal: DVec2,
+}
+
+/// A uniform grid containing every barrier within a map, used to avoid checking every barrier
+#[derive(Clone, Default)]
+pub struct BarrierGrid {
+	segments: ImmutVec<Segment>,
+	cells: FxHashMap<(i32, i32), Vec<usize>>,
+
+	min: (i32, i32),
+	max: (i32, i32),
+}
+
+impl Segment {
+	/// Checks if the segment currently blocks movement, based upon which breakables are destroyed
+	pub fn is_solid(&self, current_map: &str, broken: &Broken) -> bool {
+		match self.kind {
+			SegmentKind::Wall | SegmentKind::Door => true,
+			SegmentKind::Breakable(index) => !is_broken(broken, current_map, index),
+		}
+	}
+}
+
+impl BarrierGrid {
+	pub fn new(segments: Vec<Segment>) -> Self {
+		let mut grid = Self {
+			segments: segments.into_boxed_slice(),
+			cells: FxHashMap::default(),
+
+			min: (i32::MAX, i32::MAX),
+			max: (i32::MIN, i32::MIN),
+		};
+
+		for (index, segment) in grid.segments.iter().enumerate() {
+			let (min, max) = cell_range(segment.bar.0.dvec2(), segment.bar.1.dvec2(), 0.);
+
+			grid.min = (grid.min.0.min(min.0), grid.min.1.min(min.1));
+			grid.max = (grid.max.0.max(max.0), grid.max.1.max(max.1));
+
+			for x in min.0..=max.0 {
+				for y in min.1..=max.1 {
+					grid.cells.entry((x, y)).or_default().push(index);
+				}
+			}
+		}
+
+		grid
+	}
+
+	/// Gets every segment in the cells overlapping the box between `from` and `to`, grown by `padding`
+	pub fn query(&self, from: DVec2, to: DVec2, padding: f64) -> Vec<&Segment> {
+		let (min, max) = cell_range(from, to, padding);
+
+		let mut indices = vec![];
+		for x in min.0.max(self.min.0)..=max.0.min(self.max.0) {
+			for y in min.1.max(self.min.1)..=max.1.min(self.max.1) {
+				if let Some(cell) = self.cells.get(&(x, y)) {
+					indices.extend_from_slice(cell);
+				}
+			}
+		}
+
+		// Segments spanning several cells would otherwise be returned more than once
+		indices.sort_unstable();
+		indices.dedup();
+
+		indices.into_iter().map(|i| &self.segments[i]).collect()
+	}
+
+	/// Casts a ray from `from` to `to`, returning where it first hits a solid barrier
+	pub fn raycast(
+		&self,
+		from: DVec2,
+		to: DVec2,
+		current_map: &str,
+		broken: &Broken,
+	) -> Option<RaycastHit> {
+		let ray = to - from;
+
+		let (time, normal) = self
+			.query(from, to, 0.)
+			.into_iter()
+			.filter(|segment| segment.is_solid(current_map, broken))
+			.filter_map(|segment| {
+				let (a, b) = (segment.bar.0.dvec2(), segment.bar.1.dvec2());
+				let time = intersect_ray(from, ray, a, b)?;
+
+				let mut normal = (b - a).perp().normalize_or_zero();
+				if normal.dot(ray) > 0. {
+					normal = -normal;
+				}
+				Some((time, normal))
+			})
+			// At corners several segments are hit at once, so the one facing the ray the most is used
+			.min_by(|(time_1, normal_1), (time_2, normal_2)| {
+				time_1
+					.total_cmp(time_2)
+					.then(normal_1.dot(ray).total_cmp(&normal_2.dot(ray)))
+			})?;
+
+		Some(RaycastHit {
+			pos: from + ray * time,
+			normal,
+		})
+	}
+}
+
+/// Gets the smallest and largest cells overlapped by the box between two points
+fn cell_range(p0: DVec2, p1: DVec2, padding: f64) -> ((i32, i32), (i32, i32)) {
+	let cell = |pos: DVec2| {
+		let pos = (pos / CELL_SIZE).floor();
+		(pos.x as i32, pos.y as i32)
+	};
+
+	(
+		cell(p0.min(p1) - dvec2(padding, padding)),
+		cell(p0.max(p1) + dvec2(padding, padding)),
+	)
+}
+
+#[cfg(test)]
+mod tests;
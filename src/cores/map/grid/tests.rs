@@ -0,0 +1,73 @@
+use macroquad::math::{DVec2, dvec2};
+use raywoke::Barrier;
+
+use crate::{gameplay::breakable::Broken, utils::tup_vec::Tup64};
+
+use super::{BarrierGrid, Segment, SegmentKind};
+
+/// Creates a grid of walls, with each going between the provided points
+fn walls(bars: &[(DVec2, DVec2)]) -> BarrierGrid {
+	BarrierGrid::new(
+		bars.iter()
+			.map(|(a, b)| Segment {
+				bar: Barrier::new(a.tup64(), b.tup64()),
+				kind: SegmentKind::Wall,
+			})
+			.collect(),
+	)
+}
+
+/// Checks if nothing is in the way between the points, like `engine.has_line_of_sight`
+fn has_line_of_sight(grid: &BarrierGrid, from: DVec2, to: DVec2) -> bool {
+	grid.raycast(from, to, "", &Broken::default()).is_none()
+}
+
+#[test]
+fn clear_line_has_line_of_sight() {
+	let grid = walls(&[(dvec2(0., 50.), dvec2(100., 50.))]);
+
+	assert!(has_line_of_sight(&grid, dvec2(0., 0.), dvec2(100., 0.)));
+	// Stopping just short of the wall
+	assert!(has_line_of_sight(&grid, dvec2(50., 0.), dvec2(50., 49.)));
+}
+
+#[test]
+fn blocked_line_hits_wall() {
+	let grid = walls(&[(dvec2(0., 50.), dvec2(100., 50.))]);
+
+	let hit = grid
+		.raycast(dvec2(50., 0.), dvec2(50., 100.), "", &Broken::default())
+		.unwrap();
+
+	assert!(hit.pos.distance(dvec2(50., 50.)) < 1e-9);
+	assert!(hit.normal.distance(dvec2(0., -1.)) < 1e-9);
+	assert!(!has_line_of_sight(&grid, dvec2(50., 0.), dvec2(50., 100.)));
+}
+
+#[test]
+fn corner_hit_uses_normal_of_hit_wall() {
+	// A corner, with a horizontal wall and a vertical wall meeting at (100, 50)
+	let grid = walls(&[
+		(dvec2(0., 50.), dvec2(100., 50.)),
+		(dvec2(100., 0.), dvec2(100., 50.)),
+	]);
+	let broken = Broken::default();
+
+	// Hitting either wall right next to the corner
+	let hit = grid
+		.raycast(dvec2(95., 0.), dvec2(99., 100.), "", &broken)
+		.unwrap();
+	assert!(hit.normal.distance(dvec2(0., -1.)) < 1e-9);
+
+	let hit = grid
+		.raycast(dvec2(0., 49.), dvec2(200., 49.), "", &broken)
+		.unwrap();
+	assert!(hit.normal.distance(dvec2(-1., 0.)) < 1e-9);
+
+	// Hitting the corner itself, where the horizontal wall is the one faced the most
+	let hit = grid
+		.raycast(dvec2(80., 0.), dvec2(120., 100.), "", &broken)
+		.unwrap();
+	assert!(hit.pos.distance(dvec2(100., 50.)) < 1e-9);
+	assert!(hit.normal.distance(dvec2(0., -1.)) < 1e-9);
+}
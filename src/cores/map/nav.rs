@@ -0,0 +1,309 @@
+use std::{cmp::Ordering, collections::BinaryHeap, f64::consts::SQRT_2, sync::Arc};
+
+use macroquad::math::{DVec2, dvec2};
+use parking_lot::RwLock;
+use raywoke::Barrier;
+use rustc_hash::FxHashMap;
+
+use crate::{
+	gameplay::ecs::obj::collision::closest_point,
+	utils::{ImmutVec, tup_vec::DV2},
+};
+
+/// The width and height of each cell in the grid
+const CELL_SIZE: f64 = 16.;
+/// Clearance is not measured past this distance, so larger agents are treated as this size
+const MAX_CLEARANCE: f64 = 64.;
+/// How far away from a point to look for a walkable cell, if the point's own cell is not walkable
+const SEARCH_RADIUS: i64 = 3;
+/// How many paths can be cached for each map, before the cache is cleared
+const MAX_CACHED: usize = 1024;
+
+/// The start cell, end cell, and agent radius of a path
+type PathKey = (usize, usize, u32);
+
+/// A grid covering a map's walls, used to find paths around them
+#[derive(Default)]
+pub struct NavGrid {
+	origin: DVec2,
+	width: usize,
+	height: usize,
+
+	/// The distance from the center of each cell to the closest wall
+	clearance: ImmutVec<f64>,
+	/// Paths that have already been found, which will be the same every time since walls never move
+	cache: RwLock<FxHashMap<PathKey, Option<Arc<[DVec2]>>>>,
+}
+
+/// A cell waiting to be checked by A*
+struct Node {
+	cost: f64,
+	index: usize,
+}
+
+impl NavGrid {
+	pub fn new(walls: &[ImmutVec<Barrier>]) -> Self {
+		let bars: Vec<(DVec2, DVec2)> = walls
+			.iter()
+			.flatten()
+			.map(|bar| (bar.0.dvec2(), bar.1.dvec2()))
+			.collect();
+
+		if bars.is_empty() {
+			return Self::default();
+		}
+
+		let (min, max) = bars
+			.iter()
+			.fold((DVec2::MAX, DVec2::MIN), |(min, max), (a, b)| {
+				(min.min(*a).min(*b), max.max(*a).max(*b))
+			});
+		let size = ((max - min) / CELL_SIZE).ceil();
+
+		let mut grid = Self {
+			origin: min,
+			width: size.x as usize + 1,
+			height: size.y as usize + 1,
+
+			..Default::default()
+		};
+
+		grid.clearance = (0..grid.width * grid.height)
+			.map(|index| {
+				let center = grid.center(index);
+
+				bars.iter()
+					.map(|(a, b)| center.distance(closest_point(center, *a, *b)))
+					.fold(MAX_CLEARANCE, f64::min)
+			})
+			.collect();
+
+		grid
+	}
+
+	/// Finds a path from `from` to `to` for an agent of the provided radius.
+	/// The path does not contain `from`, and always ends at `to`.
+	pub fn find_path(&self, from: DVec2, to: DVec2, radius: f64) -> Option<Vec<DVec2>> {
+		let radius = radius.clamp(CELL_SIZE / 2., MAX_CLEARANCE);
+
+		let start = self.nearest_walkable(from, radius)?;
+		let goal = self.nearest_walkable(to, radius)?;
+		let key = (start, goal, radius.ceil() as u32);
+
+		let cached = self.cache.read().get(&key).cloned();
+		let path = if let Some(path) = cached {
+			path
+		} else {
+			let path = self
+				.search(start, goal, radius)
+				.map(|cells| Arc::from(self.smooth(&cells, radius)));
+
+			let mut cache = self.cache.write();
+			if cache.len() >= MAX_CACHED {
+				cache.clear();
+			}
+			cache.insert(key, path.clone());
+
+			path
+		}?;
+
+		// The path goes between the centers of cells, so the ends are replaced with the actual points
+		let mut path: Vec<DVec2> = path.iter().skip(1).copied().collect();
+		path.pop();
+		path.push(to);
+
+		Some(path)
+	}
+
+	/// Finds the cells between the start and goal with A*
+	fn search(&self, start: usize, goal: usize, radius: f64) -> Option<Vec<usize>> {
+		let mut open = BinaryHeap::from([Node {
+			cost: 0.,
+			index: start,
+		}]);
+		let mut costs = vec![f64::INFINITY; self.clearance.len()];
+		let mut came_from = vec![usize::MAX; self.clearance.len()];
+		costs[start] = 0.;
+
+		while let Some(Node { index, .. }) = open.pop() {
+			if index == goal {
+				let mut cells = vec![goal];
+				let mut current = goal;
+				while current != start {
+					current = came_from[current];
+					cells.push(current);
+				}
+				cells.reverse();
+
+				return Some(cells);
+			}
+
+			for (neighbor, distance) in self.neighbors(index, radius) {
+				let cost = costs[index] + distance;
+				if cost >= costs[neighbor] {
+					continue;
+				}
+
+				costs[neighbor] = cost;
+				came_from[neighbor] = index;
+				open.push(Node {
+					cost: cost + self.center(neighbor).distance(self.center(goal)),
+					index: neighbor,
+				});
+			}
+		}
+
+		None
+	}
+
+	/// Removes every point in the path that can be skipped by walking in a straight line
+	fn smooth(&self, cells: &[usize], radius: f64) -> Vec<DVec2> {
+		let points: Vec<DVec2> = cells.iter().map(|cell| self.center(*cell)).collect();
+		let mut smoothed = vec![points[0]];
+
+		let mut current = 0;
+		while current < points.len() - 1 {
+			let mut next = points.len() - 1;
+			while next > current + 1 && !self.is_clear(points[current], points[next], radius) {
+				next -= 1;
+			}
+
+			smoothed.push(points[next]);
+			current = next;
+		}
+
+		smoothed
+	}
+
+	/// Checks if an agent can walk in a straight line between two points
+	fn is_clear(&self, from: DVec2, to: DVec2, radius: f64) -> bool {
+		let steps = (from.distance(to) / (CELL_SIZE / 2.)).ceil() as usize;
+
+		(0..=steps).all(|step| {
+			let pos = from.lerp(to, step as f64 / steps.max(1) as f64);
+			self.cell(pos).is_some_and(|(x, y)| {
+				let index = self.index(x, y);
+				// Moving away from the cell's center can bring the point closer to a wall by at most that distance
+				self.clearance[index] - pos.distance(self.center(index)) > radius
+			})
+		})
+	}
+
+	/// Gets the walkable cells next to the provided one, alongside the distance to them
+	fn neighbors(&self, index: usize, radius: f64) -> impl Iterator<Item = (usize, f64)> {
+		let (x, y) = ((index % self.width) as i64, (index / self.width) as i64);
+		let walkable = move |dx: i64, dy: i64| self.walkable(x + dx, y + dy, radius);
+
+		[
+			(-1, 0),
+			(1, 0),
+			(0, -1),
+			(0, 1),
+			(-1, -1),
+			(-1, 1),
+			(1, -1),
+			(1, 1),
+		]
+		.into_iter()
+		.filter_map(move |(dx, dy)| {
+			let neighbor = walkable(dx, dy)?;
+
+			if dx == 0 || dy == 0 {
+				return Some((neighbor, CELL_SIZE));
+			}
+			// Diagonals cannot cut past the corners of walls
+			walkable(dx, 0)?;
+			walkable(0, dy)?;
+			Some((neighbor, CELL_SIZE * SQRT_2))
+		})
+	}
+
+	/// Gets the closest walkable cell to the position, if there is one nearby
+	fn nearest_walkable(&self, pos: DVec2, radius: f64) -> Option<usize> {
+		let (x, y) = self.cell(pos)?;
+		let (x, y) = (x as i64, y as i64);
+
+		let mut best: Option<(usize, f64)> = None;
+		for dx in -SEARCH_RADIUS..=SEARCH_RADIUS {
+			for dy in -SEARCH_RADIUS..=SEARCH_RADIUS {
+				let Some(index) = self.walkable(x + dx, y + dy, radius) else {
+					continue;
+				};
+
+				let distance = pos.distance(self.center(index));
+				if best.is_none_or(|(_, best)| distance < best) {
+					best = Some((index, distance));
+				}
+			}
+		}
+
+		best.map(|(index, _)| index)
+	}
+
+	/// Gets the index of the cell, if it is within the grid and an agent of the provided radius fits
+	fn walkable(&self, x: i64, y: i64, radius: f64) -> Option<usize> {
+		if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+			return None;
+		}
+
+		let index = self.index(x as usize, y as usize);
+		// A wall between the centers of two cells is at most half a cell away from one of them,
+		// so the smallest agents still can't walk between cells on either side of it
+		(self.clearance[index] > radius).then_some(index)
+	}
+
+	/// Gets the cell containing the position, if it is within the grid
+	fn cell(&self, pos: DVec2) -> Option<(usize, usize)> {
+		let cell = ((pos - self.origin) / CELL_SIZE).floor();
+
+		(cell.x >= 0. && cell.y >= 0. && cell.x < self.width as f64 && cell.y < self.height as f64)
+			.then_some((cell.x as usize, cell.y as usize))
+	}
+
+	fn index(&self, x: usize, y: usize) -> usize {
+		y * self.width + x
+	}
+
+	fn center(&self, index: usize) -> DVec2 {
+		let (x, y) = (index % self.width, index / self.width);
+		self.origin + (dvec2(x as f64, y as f64) + 0.5) * CELL_SIZE
+	}
+}
+
+// The cache is not cloned, since it will be refilled as paths are found
+impl Clone for NavGrid {
+	fn clone(&self) -> Self {
+		Self {
+			origin: self.origin,
+			width: self.width,
+			height: self.height,
+
+			clearance: self.clearance.clone(),
+			cache: RwLock::default(),
+		}
+	}
+}
+
+// Reversed, so that the cheapest node is popped first from the heap
+impl Ord for Node {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.cost.total_cmp(&self.cost)
+	}
+}
+
+impl PartialOrd for Node {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Node {
+	fn eq(&self, other: &Self) -> bool {
+		self.cost == other.cost
+	}
+}
+
+impl Eq for Node {}
+
+#[cfg(test)]
+mod tests;
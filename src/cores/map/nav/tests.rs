@@ -0,0 +1,89 @@
+use macroquad::math::{DVec2, dvec2};
+use raywoke::Barrier;
+
+use crate::{
+	gameplay::ecs::obj::collision::intersect_ray,
+	utils::{ImmutVec, tup_vec::Tup64},
+};
+
+use super::NavGrid;
+
+/// A square room split in half by a wall, with a gap in the middle of the wall 32 units wide
+fn split_room() -> (NavGrid, Vec<(DVec2, DVec2)>) {
+	let bars = vec![
+		(dvec2(0., 0.), dvec2(320., 0.)),
+		(dvec2(320., 0.), dvec2(320., 320.)),
+		(dvec2(320., 320.), dvec2(0., 320.)),
+		(dvec2(0., 320.), dvec2(0., 0.)),
+		(dvec2(160., 0.), dvec2(160., 144.)),
+		(dvec2(160., 176.), dvec2(160., 320.)),
+	];
+	let walls: ImmutVec<Barrier> = bars
+		.iter()
+		.map(|(a, b)| Barrier::new(a.tup64(), b.tup64()))
+		.collect();
+
+	(NavGrid::new(&[walls]), bars)
+}
+
+/// Checks if walking along the path from `from` would ever cross a wall
+fn crosses_wall(bars: &[(DVec2, DVec2)], from: DVec2, path: &[DVec2]) -> bool {
+	let mut prev = from;
+
+	path.iter().any(|point| {
+		let crosses = bars
+			.iter()
+			.any(|(a, b)| intersect_ray(prev, *point - prev, *a, *b).is_some());
+		prev = *point;
+		crosses
+	})
+}
+
+#[test]
+fn path_goes_through_gap() {
+	let (grid, bars) = split_room();
+	let (from, to) = (dvec2(80., 80.), dvec2(240., 80.));
+
+	let path = grid.find_path(from, to, 8.).unwrap();
+
+	assert_eq!(path.last(), Some(&to));
+	assert!(path.len() > 1);
+	assert!(!crosses_wall(&bars, from, &path));
+}
+
+#[test]
+fn large_agent_does_not_fit_through_gap() {
+	let (grid, _) = split_room();
+
+	assert!(
+		grid.find_path(dvec2(80., 80.), dvec2(240., 80.), 20.)
+			.is_none()
+	);
+	// Still able to move within its own half of the room
+	assert!(
+		grid.find_path(dvec2(80., 80.), dvec2(80., 240.), 20.)
+			.is_some()
+	);
+}
+
+#[test]
+fn unreachable_target_has_no_path() {
+	let (grid, _) = split_room();
+
+	// Far outside of the room
+	assert!(
+		grid.find_path(dvec2(80., 80.), dvec2(2000., 2000.), 8.)
+			.is_none()
+	);
+}
+
+#[test]
+fn cache_is_keyed_by_radius() {
+	let (grid, _) = split_room();
+	let (from, to) = (dvec2(80., 80.), dvec2(240., 80.));
+
+	assert!(grid.find_path(from, to, 8.).is_some());
+	// Would be found if the small agent's path was reused
+	assert!(grid.find_path(from, to, 20.).is_none());
+	assert_eq!(grid.cache.read().len(), 2);
+}
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Serialize, Deserialize)]
 pub struct NpcType {
 	pub sprite: String,
+	/// The mass used when colliding with other entities, if the NPC should collide with them
+	#[serde(default)]
+	pub mass: Option<f64>,
 	pub goals: ImmutVec<String>,
 	pub messages: ImmutVec<Message>,
 }
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::{
+	gameplay::{combat::AttackType, ecs::obj::hitbox::Hitbox},
+	prelude::*,
+	utils::ImmutVec,
+};
+
+/// A struct containing how an attack parries other attacks
+#[derive(Clone, Deserialize)]
+pub struct ParryType {
+	/// How long after being created the attack is able to parry
+	pub window: f64,
+	/// What happens to each type of attack that can be parried.
+	/// Attack types that are not listed cannot be parried.
+	pub parries: FxHashMap<AttackType, ParryResult>,
+	/// Multiplies the damage of the parrying attack, which is then added to the parried attack
+	pub damage_bonus: f64,
+	/// How long the game pauses for when parrying
+	pub hitstop: f64,
+	/// How much gun energy the player gains when they parry
+	#[serde(default)]
+	pub energy: f64,
+	/// One of these is played at random when parrying
+	#[serde(default)]
+	pub sounds: ImmutVec<String>,
+}
+
+/// What a parried attack becomes
+#[derive(Clone, Deserialize)]
+pub struct ParryResult {
+	/// The attack type the attack is turned into, if it should change
+	#[serde(default)]
+	pub becomes: Option<AttackType>,
+	/// The hitbox the attack is given, if it should change.
+	/// If the attack type changes without one, the new type's usual hitbox is used.
+	#[serde(default)]
+	pub hitbox: Option<Hitbox>,
+	/// The sprite the attack is given, if it should change
+	#[serde(default)]
+	pub sprite: Option<String>,
+	/// What the attack's lifetime is set to, if it should change
+	#[serde(default)]
+	pub lifetime: Option<f64>,
+	/// Status effects that the attack applies to what it hits from then on
+	#[serde(default)]
+	pub effects: ImmutVec<String>,
+	/// If true, the attack is sent towards where its new owner is aiming
+	#[serde(default)]
+	pub redirect: bool,
+	/// If true, attacks can be parried by attacks with the same owner
+	#[serde(default)]
+	pub same_owner: bool,
+}
+
+/// Provides a `HashMap` containing all `ParryTypes`
+pub fn get_parrytypes() -> FxHashMap<String, ParryType> {
+	let parrytypes: FxHashMap<String, ParryType> = get_files("parries")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path::<ParryType>(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("ParryType {str} failed to load: {e}");
+				None
+			}
+			Ok(parrytype) => {
+				info!("ParryType {str} loaded!");
+				Some((str, parrytype))
+			}
+		})
+		.collect();
+
+	parrytypes
+}
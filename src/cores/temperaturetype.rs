@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::prelude::*;
+
+/// A struct containing how an entity's temperature behaves.
+///
+/// Temperature rises from using weapons and taking damage of types with heat, and cools over time.
+#[derive(Clone, Deserialize)]
+pub struct TemperatureType {
+	/// How much the temperature falls over each unit of time
+	pub cooling: f64,
+
+	/// Past this temperature, damage dealt is multiplied by `warm_damage`
+	pub warm: f64,
+	pub warm_damage: f64,
+	/// Past this temperature, damage dealt is multiplied by `hot_damage` instead
+	pub hot: f64,
+	pub hot_damage: f64,
+
+	/// Reaching this temperature causes overheating
+	pub max: f64,
+	/// The status effect applied when overheating
+	pub overheat_effect: String,
+	/// What the temperature is set to after overheating
+	pub overheat_reset: f64,
+}
+
+/// Provides a `HashMap` containing all `TemperatureTypes`
+pub fn get_temperaturetypes() -> FxHashMap<String, TemperatureType> {
+	let temperaturetypes: FxHashMap<String, TemperatureType> = get_files("temperature")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path::<TemperatureType>(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("TemperatureType {str} failed to load: {e}");
+				None
+			}
+			Ok(temperaturetype) => {
+				info!("TemperatureType {str} loaded!");
+				Some((str, temperaturetype))
+			}
+		})
+		.collect();
+
+	temperaturetypes
+}
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{gen_name, get_files, read_from_path};
+
+use crate::{
+	gameplay::{
+		combat::{AttackType, pattern::Pattern, projectile::Projectile},
+		ecs::obj::hitbox::Hitbox,
+	},
+	prelude::*,
+	utils::ImmutVec,
+};
+
+/// A struct containing the stats of a weapon the player can use
+#[derive(Clone, Deserialize)]
+pub struct WeaponType {
+	pub slot: WeaponSlot,
+	/// Where the weapon is placed among the others in its slot, lowest first
+	#[serde(default)]
+	pub order: i64,
+	/// If true, the weapon has to be unlocked before it can be used
+	#[serde(default)]
+	pub locked: bool,
+
+	pub cooldown: f64,
+	/// How much temperature is gained each time the weapon is used
+	#[serde(default)]
+	pub heat: f64,
+	/// How much gun energy is used up each time the weapon is used
+	#[serde(default)]
+	pub energy_cost: f64,
+	/// How much gun energy is gained each time the weapon's attacks hit an enemy
+	#[serde(default)]
+	pub energy_gain: f64,
+	pub attack: AttackType,
+	pub damage: f64,
+	/// The type of damage the weapon deals, if it should differ from that of its attack type
+	#[serde(default)]
+	pub damage_type: Option<String>,
+	/// The status effects applied to what the weapon hits
+	#[serde(default)]
+	pub effects: ImmutVec<String>,
+	/// How the weapon's attacks parry, if it should differ from that of its attack type
+	#[serde(default)]
+	pub parry: Option<String>,
+	/// How the weapon's projectiles move, which is ignored by other attack types
+	#[serde(default)]
+	pub projectile: Projectile,
+	/// Fires several attacks at once in the provided pattern, instead of a single attack
+	#[serde(default)]
+	pub pattern: Option<Pattern>,
+	/// The shape of the weapon's attacks, if it should differ from that of its attack type
+	#[serde(default)]
+	pub hitbox: Option<Hitbox>,
+	pub size: f64,
+	pub sprite: String,
+	/// One of these is played at random whenever the weapon is used
+	#[serde(default)]
+	pub sounds: ImmutVec<String>,
+	/// A script containing an `on_attack` function, called whenever the weapon is used
+	#[serde(default)]
+	pub script: Option<String>,
+	/// A script that controls the attacks created by the weapon
+	#[serde(default)]
+	pub attack_script: Option<String>,
+	/// The attacks the weapon cycles through when used repeatedly, with the last being the finisher.
+	/// Weapons without a combo always use the same attack.
+	#[serde(default)]
+	pub combo: ImmutVec<ComboStep>,
+}
+
+/// One of the attacks within a weapon's combo, with any unset stats being taken from the weapon
+#[derive(Clone, Deserialize)]
+pub struct ComboStep {
+	#[serde(default)]
+	pub cooldown: Option<f64>,
+	/// How long the weapon can go unused after its cooldown, before the combo is reset
+	#[serde(default)]
+	pub window: f64,
+	#[serde(default)]
+	pub damage: Option<f64>,
+	#[serde(default)]
+	pub size: Option<f64>,
+	#[serde(default)]
+	pub sprite: Option<String>,
+	/// Status effects applied on top of those of the weapon
+	#[serde(default)]
+	pub effects: ImmutVec<String>,
+}
+
+/// Which button a weapon is used with
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum WeaponSlot {
+	Sword,
+	Gun,
+}
+
+/// Provides a `HashMap` containing all `WeaponTypes`
+pub fn get_weapontypes() -> FxHashMap<String, WeaponType> {
+	let weapontypes: FxHashMap<String, WeaponType> = get_files("weapons")
+		.iter()
+		.map(|dir| (gen_name(dir), read_from_path(dir)))
+		.filter_map(|(str, result)| match result {
+			Err(e) => {
+				warn!("WeaponType {str} failed to load: {e}");
+				None
+			}
+			Ok(weapontype) => {
+				info!("WeaponType {str} loaded!");
+				Some((str, weapontype))
+			}
+		})
+		.collect();
+
+	weapontypes
+}
@@ -21,6 +21,29 @@ pub struct Config {
 
 	/// How much to scale the game when rendering
 	pub screen_scale: f64,
+
+	/// If true, the hitboxes of entities and attacks are drawn
+	#[serde(default)]
+	pub show_hitboxes: bool,
+
+	/// Which kinds of feedback are given when hitting things
+	#[serde(default)]
+	pub feedback: FeedbackConfig,
+}
+
+/// Toggles for each kind of feedback given when hitting things, which are all enabled by default
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)] // Each one is an independent toggle, rather than a state
+pub struct FeedbackConfig {
+	/// Numbers showing how much damage each hit dealt
+	pub damage_numbers: bool,
+	/// Things flash white when hit
+	pub hit_flash: bool,
+	/// The game briefly pauses on heavy hits and parries
+	pub hitstop: bool,
+	/// Rings burst out of critical hits and parries
+	pub flourishes: bool,
 }
 
 impl Config {
@@ -52,6 +75,20 @@ impl Default for Config {
 			start_map: "default:test".into(),
 			lang: "en".into(),
 			screen_scale: 3.,
+
+			show_hitboxes: false,
+			feedback: FeedbackConfig::default(),
+		}
+	}
+}
+
+impl Default for FeedbackConfig {
+	fn default() -> Self {
+		Self {
+			damage_numbers: true,
+			hit_flash: true,
+			hitstop: true,
+			flourishes: true,
 		}
 	}
 }
@@ -0,0 +1,84 @@
+use std::{fs::File, path::Path};
+
+use bincode::{Decode, Encode, config, decode_from_slice, encode_to_vec};
+use tracing::{error, info};
+
+use crate::gameplay::input::Input;
+
+use super::save::Save;
+
+/// A recording of a gameplay session, which can be played back to reproduce it exactly
+#[derive(Encode, Decode)]
+pub struct Replay {
+	/// The save at the start of the session, which also contains the RNG seed
+	pub save: Save,
+	pub start_map: String,
+	/// The input for every tick of the session
+	pub inputs: Vec<Input>,
+}
+
+impl Replay {
+	pub fn new(save: Save, start_map: String) -> Self {
+		Self {
+			save,
+			start_map,
+			inputs: vec![],
+		}
+	}
+
+	pub fn read(dir: impl AsRef<Path>) -> Option<Self> {
+		let file = match File::open(dir) {
+			Ok(ok) => ok,
+			Err(e) => {
+				error!("Failed to open replay file: {e}");
+				return None;
+			}
+		};
+
+		let bytes = match zstd::decode_all(file) {
+			Ok(ok) => ok,
+			Err(e) => {
+				error!("Failed to decompress replay file: {e}");
+				return None;
+			}
+		};
+
+		match decode_from_slice(&bytes, config::standard()) {
+			Ok((ok, _)) => {
+				info!("Replay file loaded!");
+				Some(ok)
+			}
+			Err(e) => {
+				error!("Failed to decode replay file: {e}");
+				None
+			}
+		}
+	}
+
+	/// Writes the replay to the provided file.
+	///
+	/// Unlike [`Save::save`], this blocks, so that the replay is not lost if the game exits right after.
+	pub fn save(&self, dir: impl AsRef<Path>) {
+		let bytes = match encode_to_vec(self, config::standard()) {
+			Ok(ok) => ok,
+			Err(e) => {
+				error!("Failed to encode replay: {e}");
+				return;
+			}
+		};
+
+		let compressed = match zstd::bulk::compress(&bytes, 0) {
+			Ok(ok) => ok,
+			Err(e) => {
+				error!("Failed to compress replay: {e}");
+				return;
+			}
+		};
+
+		if let Err(e) = std::fs::write(dir, compressed) {
+			error!("Failed to write replay: {e}")
+		} else {
+			info!("Replay saved successfully! ({} ticks)", self.inputs.len())
+		}
+	}
+}
@@ -1,17 +1,26 @@
 use std::{
 	fs::File,
 	path::{Path, PathBuf},
+	sync::Arc,
 };
 
 use bincode::{Decode, Encode, config, decode_from_slice, encode_to_vec};
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use tracing::{error, info};
 
 use crate::utils::random_seed;
 
-#[derive(Encode, Decode)]
+#[derive(Clone, Encode, Decode)]
 pub struct Save {
 	pub seen_maps: FxHashSet<String>,
+	pub completed_arenas: FxHashSet<String>,
+	pub collected_pickups: FxHashSet<String>,
+	/// The indices of destroyed breakables in each map, which is shared with Lua for raycasts
+	pub broken: Arc<FxHashMap<String, FxHashSet<usize>>>,
+	pub unlocked_weapons: FxHashSet<String>,
+	pub money: u64,
+	/// Extra max health gained from pickups
+	pub max_health: f64,
 	pub seed: u64,
 }
 
@@ -77,6 +86,12 @@ impl Default for Save {
 	fn default() -> Self {
 		Self {
 			seen_maps: FxHashSet::default(),
+			completed_arenas: FxHashSet::default(),
+			collected_pickups: FxHashSet::default(),
+			broken: Arc::default(),
+			unlocked_weapons: FxHashSet::default(),
+			money: 0,
+			max_health: 0.,
 			seed: random_seed(),
 		}
 	}
@@ -4,55 +4,123 @@ use draw::{draw, render::darken_screen};
 use macroquad::prelude::*;
 use paused::Paused;
 use stecs::prelude::*;
+use tracing::info;
 
 use crate::{
 	State,
-	data::save::Save,
+	cores::weapontype::WeaponSlot,
+	data::{replay::Replay, save::Save},
 	utils::{
-		resources::config::access_config, smart_time, update_delta_time, update_mouse_pos,
-		update_screen_size,
+		TICK, TICK_LENGTH, delta_time, resources::config::access_config, update_delta_time,
+		update_mouse_pos, update_screen_size,
 	},
 };
 
-use combat::{AttackType, Owner, handle_combat};
+use arena::update_arenas;
+use breakable::{remove_broken, share_broken};
+use combat::{
+	AttackType, Owner,
+	feedback::{Feedback, handle_feedback},
+	handle_combat,
+	pattern::spawn_queued_attacks,
+	script::script_expire,
+};
 
-use ecs::{World, behavior::handle_behavior};
+use ecs::{World, behavior::handle_behavior, remove_where};
+use input::{Action, InputReader, input};
 
+use pickup::collect_pickups;
 use player::{Player, swap_weapons};
 
+pub mod arena;
+pub mod breakable;
 pub mod combat;
 pub mod doors;
 pub mod draw;
 pub mod ecs;
 pub mod enemy;
+pub mod input;
 pub mod npc;
 pub mod paused;
+pub mod pickup;
 pub mod player;
 
+#[cfg(test)]
+mod tests;
+
 // TODO: Make more configurable.
 pub const SAVE_DIR: &str = "./save.evs";
 
+/// The most simulation ticks that can run within a single frame.
+/// Any time beyond this is dropped, so that a long hitch does not freeze the game while it catches up.
+const MAX_TICKS_PER_FRAME: u32 = 8;
+
 pub struct Gameplay {
 	pub world: World,
 	pub current_map: String,
 	pub paused: Paused,
 	pub save: Save,
-	save_dir: PathBuf,
+	/// Where the save is written once gameplay ends. Replays do not write their save.
+	save_dir: Option<PathBuf>,
+	/// How far rendering is between the previous tick and the current one, from `0` to `1`
+	pub interpolation: f64,
+	pub input: InputReader,
+	/// Feedback shown for hits and parries
+	pub feedback: Feedback,
 }
 
 impl Gameplay {
-	pub fn new(save: impl AsRef<Path>) -> Self {
+	/// Starts gameplay from the provided save, recording input to `record` if it is provided
+	pub fn new(save: impl AsRef<Path>, record: Option<&Path>) -> Self {
+		let save_dir = save.as_ref().to_path_buf();
+		let save = Save::read(save);
+		let start_map = access_config().start_map.clone();
+
+		let input = match record {
+			Some(dir) => InputReader::record(
+				Replay::new(save.clone(), start_map.clone()),
+				dir.to_path_buf(),
+			),
+			None => InputReader::live(),
+		};
+
+		Self::from_parts(save, start_map, Some(save_dir), input)
+	}
+
+	/// Starts gameplay that plays back the provided replay
+	pub fn from_replay(replay: Replay) -> Self {
+		Self::from_parts(
+			replay.save,
+			replay.start_map,
+			None,
+			InputReader::replay(replay.inputs),
+		)
+	}
+
+	fn from_parts(
+		save: Save,
+		current_map: String,
+		save_dir: Option<PathBuf>,
+		input: InputReader,
+	) -> Self {
 		let mut gameplay = Self {
 			world: World::default(),
-			current_map: access_config().start_map.clone(),
+			current_map,
 			paused: Paused::None,
-			save: Save::read(&save),
-			save_dir: save.as_ref().to_path_buf(),
+			save,
+			save_dir,
+			interpolation: 1.,
+			input,
+			feedback: Feedback::default(),
 		};
 
 		// Initial World setup
-		gameplay.world.player.insert(Player::new());
-		gameplay.world.populate(&gameplay.current_map);
+		gameplay.world.player.insert(Player::new(&gameplay.save));
+		gameplay
+			.world
+			.populate(&gameplay.current_map, &gameplay.save);
+
+		share_broken(&gameplay.save.broken);
 
 		// Seeding the RNG
 		rand::srand(gameplay.save.seed);
@@ -60,25 +128,75 @@ impl Gameplay {
 		gameplay
 	}
 
+	/// Advances the simulation by a single tick.
+	/// Returns `false` without doing anything if the replay being played has finished.
+	pub fn tick(&mut self) -> bool {
+		if !self.input.advance() {
+			return false;
+		}
+		self.store_prev_positions();
+
+		self.get_npc_dialogue();
+		self.change_weapon();
+		spawn_queued_attacks(self);
+		self.update_health();
+		self.update_effects();
+		self.update_temperature();
+		self.remove_dead_enemies();
+		self.remove_old_attacks();
+		remove_broken(self);
+		update_arenas(self);
+		collect_pickups(self);
+		self.try_player_death();
+
+		handle_combat(self);
+		handle_feedback(self);
+		handle_behavior(self);
+
+		self.update_sprites();
+		true
+	}
+
+	/// Stores the position of every moving Obj, so that rendering can interpolate from it
+	fn store_prev_positions(&mut self) {
+		for obj in query!(
+			[
+				self.world.player,
+				self.world.enemies,
+				self.world.npcs,
+				self.world.attacks
+			],
+			(&mut obj)
+		) {
+			obj.prev_pos = obj.pos;
+		}
+	}
+
+	/// Advances the animations of every sprite
+	fn update_sprites(&mut self) {
+		for (sprite, obj) in query!(
+			[
+				self.world.player,
+				self.world.enemies,
+				self.world.corpses,
+				self.world.npcs,
+				self.world.attacks,
+				self.world.pickups,
+				self.world.breakables
+			],
+			(&mut sprite, &obj)
+		) {
+			sprite.update(*obj);
+		}
+	}
+
 	fn pause(&mut self) -> Option<State> {
 		if self.paused.is_paused() {
 			darken_screen();
-
-			for inventory in query!(self.world.player, (&mut inventory)) {
-				for sword in &mut inventory.swords {
-					if sword.cooldown <= 0. {
-						sword.cooldown = 3.;
-					}
-				}
-				for gun in &mut inventory.guns {
-					if gun.cooldown <= 0. {
-						gun.cooldown = 3.;
-					}
-				}
-			}
+			// Stored within the input, so that replays know when the game was paused
+			self.input.pause();
 		}
 
-		self.get_npc_dialogue();
 		self.paused.pause()
 	}
 
@@ -91,7 +209,7 @@ impl Gameplay {
 			query!(self.world.npcs, (&obj, &messages, &mut messages_cooldown))
 		{
 			if *messages_cooldown > 0. {
-				*messages_cooldown -= smart_time();
+				*messages_cooldown -= TICK;
 				continue;
 			}
 
@@ -117,47 +235,74 @@ impl Gameplay {
 	}
 
 	fn change_weapon(&mut self) {
-		for (inventory, obj) in query!(self.world.player, (&mut inventory, &obj)) {
+		let input = input();
+
+		for (inventory, obj, temperature) in
+			query!(self.world.player, (&mut inventory, &obj, &mut temperature))
+		{
+			// Stops the click used to unpause from also attacking
+			if input.was_paused() {
+				for info in inventory.swords.iter_mut().chain(inventory.guns.iter_mut()) {
+					if info.cooldown <= 0. {
+						info.cooldown = 3.;
+					}
+				}
+			}
+
 			// Switching weapons
-			if access_config().keymap.change_sword.is_pressed() {
+			if input.is_pressed(Action::ChangeSword) {
 				inventory.current_sword = swap_weapons(inventory.current_sword, &inventory.swords);
 			}
-			if access_config().keymap.change_gun.is_pressed() {
+			if input.is_pressed(Action::ChangeGun) {
 				inventory.current_gun = swap_weapons(inventory.current_gun, &inventory.guns);
 			}
 
 			// Cooldown
-			for sword in &mut inventory.swords {
-				if sword.cooldown >= 0. {
-					sword.cooldown -= smart_time();
-				}
-			}
-			for gun in &mut inventory.guns {
-				if gun.cooldown >= 0. {
-					gun.cooldown -= smart_time();
-				}
+			for info in inventory.swords.iter_mut().chain(inventory.guns.iter_mut()) {
+				info.update();
 			}
 
 			// Creating attacks
-			if access_config().keymap.sword.is_down()
-				&& inventory.swords[inventory.current_sword].cooldown <= 0.
+			if input.is_down(Action::Sword)
+				&& let Some(attacks) = inventory.attack(WeaponSlot::Sword, obj.pos, temperature)
 			{
-				self.world.attacks.insert(inventory.attack_sword(obj.pos));
+				self.world.queued_attacks.extend(attacks);
 			}
-			if access_config().keymap.gun.is_down()
-				&& inventory.guns[inventory.current_gun].cooldown <= 0.
+			if input.is_down(Action::Gun)
+				&& let Some(attacks) = inventory.attack(WeaponSlot::Gun, obj.pos, temperature)
 			{
-				self.world.attacks.insert(inventory.attack_gun(obj.pos));
+				self.world.queued_attacks.extend(attacks);
 			}
 		}
 	}
 
 	fn update_health(&mut self) {
-		for hp in query!([self.world.player, self.world.enemies], (&mut health)) {
+		for hp in query!(
+			[self.world.player, self.world.enemies, self.world.breakables],
+			(&mut health)
+		) {
 			hp.update();
 		}
 	}
 
+	fn update_effects(&mut self) {
+		for (obj, hp, sprite, effects) in query!(
+			[self.world.player, self.world.enemies],
+			(&mut obj, &mut health, &mut sprite, &mut effects)
+		) {
+			effects.update(obj, hp, sprite);
+		}
+	}
+
+	fn update_temperature(&mut self) {
+		for (temperature, hp, effects) in query!(
+			self.world.player,
+			(&mut temperature, &mut health, &mut effects)
+		) {
+			temperature.update(hp, effects);
+		}
+	}
+
 	/// Handling the player's death (WIP)
 	fn try_player_death(&mut self) {
 		let mut player_is_dead = false;
@@ -174,54 +319,46 @@ impl Gameplay {
 				self.world.player.remove(0);
 			}
 
-			self.world.player.insert(Player::new());
+			self.world.player.insert(Player::new(&self.save));
 
-			self.world.populate(&self.current_map);
+			self.world.populate(&self.current_map, &self.save);
 		}
 	}
 
 	/// Handling old attacks
 	fn remove_old_attacks(&mut self) {
-		let mut to_remove: usize = 0;
-		while {
-			let mut atk_to_remove = false;
-
-			for (index, atk) in self.world.attacks.iter() {
-				if match atk.atk_type {
-					AttackType::Physical | AttackType::Burst => atk.sprite.anim_completed(),
-					_ => *atk.lifetime <= 0.,
-				} {
-					to_remove = index;
-					atk_to_remove = true;
-					break;
-				}
+		remove_where(&mut self.world.attacks, |attacks, id| {
+			let Some(mut atk) = attacks.get_mut(id) else {
+				return false;
+			};
+
+			let expired = match atk.atk_type {
+				AttackType::Physical | AttackType::Burst => atk.sprite.anim_completed(),
+				_ => *atk.lifetime <= 0.,
+			};
+			if expired {
+				script_expire(&mut atk);
 			}
-
-			atk_to_remove
-		} {
-			self.world.attacks.remove(to_remove);
-		}
+			expired
+		});
 	}
 
-	/// Handling dead enemies.
-	/// TODO: Death animation
+	/// Starts the death of any dead enemies, and removes the corpses that have finished their death animation
 	fn remove_dead_enemies(&mut self) {
-		let mut to_remove: usize = 0;
-		while {
-			let mut enemy_to_remove = false;
-
-			for (index, enemy) in self.world.enemies.iter() {
-				if enemy.health.should_kill() {
-					to_remove = index;
-					enemy_to_remove = true;
-					break;
-				}
+		let obj_player = self.world.player.obj.first().copied().unwrap_or_default();
+
+		let dead = remove_where(&mut self.world.enemies, |enemies, id| {
+			enemies.health[id].should_kill()
+		});
+		for enemy in dead {
+			if let Some(corpse) = enemy.die(&obj_player, &mut self.world.pickups) {
+				self.world.corpses.insert(corpse);
 			}
-
-			enemy_to_remove
-		} {
-			self.world.enemies.remove(to_remove);
 		}
+
+		remove_where(&mut self.world.corpses, |corpses, id| {
+			corpses.sprite[id].anim_completed()
+		});
 	}
 }
 
@@ -229,17 +366,22 @@ impl Gameplay {
 // TODO: remove this, add actual saving mechanism
 impl Drop for Gameplay {
 	fn drop(&mut self) {
-		self.save.save(&self.save_dir);
+		if let Some(save_dir) = &self.save_dir {
+			self.save.save(save_dir);
+		}
+		self.input.finish();
 	}
 }
 
-pub async fn gameplay(save: impl AsRef<Path>) -> State {
-	let mut gameplay = Gameplay::new(save);
+pub async fn gameplay(mut gameplay: Gameplay) -> State {
+	// Time that has passed, but not yet been simulated
+	let mut accumulator = 0.;
 
 	loop {
 		update_delta_time();
 		update_mouse_pos();
 		update_screen_size();
+		gameplay.input.poll();
 
 		draw(&mut gameplay).await;
 
@@ -248,6 +390,7 @@ pub async fn gameplay(save: impl AsRef<Path>) -> State {
 			match state {
 				State::Gameplay => {
 					gameplay.paused = Paused::None;
+					accumulator = 0.;
 					next_frame().await;
 					continue;
 				}
@@ -258,20 +401,45 @@ pub async fn gameplay(save: impl AsRef<Path>) -> State {
 		}
 
 		if gameplay.paused.is_paused() {
+			accumulator = 0.;
 			next_frame().await;
 			continue;
 		}
 		// Normal gameplay continues
 
-		gameplay.change_weapon();
-		gameplay.update_health();
-		gameplay.remove_dead_enemies();
-		gameplay.remove_old_attacks();
-		gameplay.try_player_death();
+		accumulator += delta_time();
 
-		handle_combat(&mut gameplay);
-		handle_behavior(&mut gameplay);
+		let mut ticks = 0;
+		while accumulator >= TICK_LENGTH && ticks < MAX_TICKS_PER_FRAME {
+			if !gameplay.tick() {
+				info!("Replay finished");
+				return State::Menu;
+			}
+			accumulator -= TICK_LENGTH;
+			ticks += 1;
+
+			if gameplay.paused.is_paused() {
+				accumulator = 0.;
+				break;
+			}
+		}
+		accumulator = accumulator.min(TICK_LENGTH);
+
+		gameplay.interpolation = accumulator / TICK_LENGTH;
 
 		next_frame().await;
 	}
 }
+
+/// Runs gameplay as fast as possible without rendering it, until the replay being played finishes
+pub fn simulate(gameplay: &mut Gameplay) {
+	let mut ticks: u64 = 0;
+
+	while gameplay.tick() {
+		// Pausing only stops time from passing, so it can be skipped entirely
+		gameplay.paused = Paused::None;
+		ticks += 1;
+	}
+
+	info!("Replay finished after {ticks} ticks");
+}
@@ -0,0 +1,166 @@
+use macroquad::math::DVec2;
+use serde::Deserialize;
+use stecs::prelude::*;
+use tracing::{error, info};
+
+use crate::{
+	cores::enemytype::EnemyType,
+	prelude::FxHashMap,
+	utils::{ImmutVec, TICK, resources::maps::access_map},
+};
+
+use super::{Gameplay, ecs::obj::Obj, enemy::Enemy};
+
+/// An arena, as it is declared within a map
+#[derive(Deserialize)]
+pub struct ArenaBuilder {
+	name: String,
+	trigger: DVec2,
+	radius: f64,
+	waves: Vec<WaveBuilder>,
+}
+
+#[derive(Deserialize)]
+struct WaveBuilder {
+	enemies: Vec<String>,
+	#[serde(default)]
+	spawn_points: Vec<DVec2>,
+	#[serde(default)]
+	delay: f64,
+	#[serde(default)]
+	remaining: usize,
+}
+
+/// An encounter that locks the map's doors and spawns waves of enemies
+#[derive(Clone)]
+pub struct Arena {
+	pub name: String,
+	trigger: Obj,
+	waves: ImmutVec<Wave>,
+}
+
+#[derive(Clone)]
+struct Wave {
+	enemies: ImmutVec<EnemyType>,
+	/// Enemies are spawned at these points in order, looping if there are more enemies than points
+	spawn_points: ImmutVec<DVec2>,
+	/// How long to wait before spawning, after the previous wave is cleared enough
+	delay: f64,
+	/// How many enemies may still be alive for this wave to start
+	remaining: usize,
+}
+
+/// The arena that the player is currently fighting in
+pub struct ActiveArena {
+	index: usize,
+	wave: usize,
+	delay: f64,
+}
+
+impl ArenaBuilder {
+	pub fn build(self, enemytypes: &FxHashMap<String, EnemyType>) -> Arena {
+		let trigger = Obj::new(self.trigger, self.trigger, self.radius);
+
+		Arena {
+			name: self.name,
+			trigger,
+			waves: self
+				.waves
+				.into_iter()
+				.map(|wave| Wave {
+					enemies: wave
+						.enemies
+						.iter()
+						.filter_map(|name| {
+							let enemytype = enemytypes.get(name).cloned();
+							if enemytype.is_none() {
+								error!("EnemyType {name} not found! Skipping...");
+							}
+							enemytype
+						})
+						.collect(),
+					spawn_points: if wave.spawn_points.is_empty() {
+						[trigger.pos].into()
+					} else {
+						wave.spawn_points.into_boxed_slice()
+					},
+					delay: wave.delay,
+					remaining: wave.remaining,
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Arena {
+	/// Gets the key used to store the arena's completion within the save
+	fn save_key(&self, current_map: &str) -> String {
+		format!("{current_map}/{}", self.name)
+	}
+}
+
+/// Starts arenas when the player enters their trigger, and spawns the waves of the current one
+pub fn update_arenas(gameplay: &mut Gameplay) {
+	let current_map = gameplay.current_map.clone();
+	let map = access_map(&current_map);
+
+	let Some(active) = &mut gameplay.world.arena else {
+		let Some(obj_player) = gameplay.world.player.obj.first() else {
+			return;
+		};
+
+		for (index, arena) in map.arenas.iter().enumerate() {
+			if arena.trigger.is_touching(obj_player)
+				&& !gameplay
+					.save
+					.completed_arenas
+					.contains(&arena.save_key(&current_map))
+			{
+				info!("Arena {} started", arena.name);
+				gameplay.world.arena = Some(ActiveArena {
+					index,
+					wave: 0,
+					delay: arena.waves.first().map_or(0., |wave| wave.delay),
+				});
+				return;
+			}
+		}
+		return;
+	};
+
+	let arena = &map.arenas[active.index];
+	// Enemies that were already in the map don't need to be killed to clear waves
+	let enemies_remaining = query!(gameplay.world.enemies, (&in_arena))
+		.filter(|in_arena| **in_arena)
+		.count();
+
+	let Some(wave) = arena.waves.get(active.wave) else {
+		// Every wave has been spawned, so the arena is complete once all enemies are dead
+		if enemies_remaining == 0 {
+			info!("Arena {} completed", arena.name);
+			gameplay
+				.save
+				.completed_arenas
+				.insert(arena.save_key(&current_map));
+			gameplay.world.arena = None;
+		}
+		return;
+	};
+
+	if enemies_remaining > wave.remaining {
+		return;
+	}
+	if active.delay > 0. {
+		active.delay -= TICK;
+		return;
+	}
+
+	for (enemytype, pos) in wave.enemies.iter().zip(wave.spawn_points.iter().cycle()) {
+		let mut enemy = Enemy::from_type(enemytype, pos);
+		enemy.in_arena = true;
+		gameplay.world.enemies.insert(enemy);
+	}
+
+	active.wave += 1;
+	active.delay = arena.waves.get(active.wave).map_or(0., |wave| wave.delay);
+}
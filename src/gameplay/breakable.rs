@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use macroquad::math::DVec2;
+use raywoke::Barrier;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use stecs::prelude::*;
+use tracing::info;
+
+use crate::utils::{ImmutVec, resources::scripts::lua, tup_vec::Tup64};
+
+use super::{
+	Gameplay,
+	combat::AttackType,
+	ecs::{
+		health::Health,
+		obj::{Obj, hitbox::Hitbox},
+		remove_where,
+		sprite::{Frames, Rotation, Sprite},
+	},
+};
+
+/// The breakables that have been destroyed in each map, by their index within the map
+pub type Broken = FxHashMap<String, FxHashSet<usize>>;
+
+/// A breakable object, as it is declared within a map
+#[derive(Deserialize)]
+pub struct BreakableBuilder {
+	points: Vec<DVec2>,
+	health: f64,
+	sprite: String,
+	weak_to: Vec<AttackType>,
+}
+
+/// The stats of a breakable object within a map
+#[derive(Clone)]
+pub struct BreakableType {
+	pub barriers: ImmutVec<Barrier>,
+	hitbox: Hitbox,
+	center: DVec2,
+	size: f64,
+	health: f64,
+	sprite: String,
+	weak_to: ImmutVec<AttackType>,
+}
+
+/// A wall or prop that can be destroyed by attacks of the right type
+#[derive(SplitFields)]
+pub struct Breakable {
+	obj: Obj,
+	health: Health,
+	sprite: Sprite,
+	hitbox: Hitbox,
+
+	weak_to: ImmutVec<AttackType>,
+	index: usize,
+}
+
+impl BreakableBuilder {
+	pub fn build(self) -> BreakableType {
+		let center = self.points.iter().sum::<DVec2>() / self.points.len().max(1) as f64;
+
+		BreakableType {
+			barriers: (0..self.points.len())
+				.map(|i| {
+					Barrier::new(
+						self.points[i].tup64(),
+						self.points[(i + 1) % self.points.len()].tup64(),
+					)
+				})
+				.collect(),
+			hitbox: Hitbox::Polygon(self.points.iter().map(|point| *point - center).collect()),
+			center,
+			size: self
+				.points
+				.iter()
+				.map(|point| point.distance(center))
+				.fold(0., f64::max),
+			health: self.health,
+			sprite: self.sprite,
+			weak_to: self.weak_to.into_boxed_slice(),
+		}
+	}
+}
+
+impl Breakable {
+	pub fn from_type(breakabletype: &BreakableType, index: usize) -> Self {
+		let obj = Obj::new(
+			breakabletype.center,
+			breakabletype.center,
+			breakabletype.size,
+		);
+
+		Self {
+			obj,
+			health: Health::new(breakabletype.health),
+			sprite: Sprite::new(
+				obj,
+				&breakabletype.sprite,
+				Rotation::Static,
+				Frames::new_static(),
+				FxHashMap::default(),
+			),
+			hitbox: breakabletype.hitbox.clone(),
+
+			weak_to: breakabletype.weak_to.clone(),
+			index,
+		}
+	}
+}
+
+/// Checks if the breakable at the provided index in the map has been destroyed
+pub fn is_broken(broken: &Broken, current_map: &str, index: usize) -> bool {
+	broken
+		.get(current_map)
+		.is_some_and(|broken| broken.contains(&index))
+}
+
+/// Gives Lua the same destroyed breakables as gameplay, as scripts can't access the save
+pub fn share_broken(broken: &Arc<Broken>) {
+	lua().set_app_data(Arc::clone(broken));
+}
+
+/// Removes breakables that have run out of health, and stores that they were destroyed
+pub fn remove_broken(gameplay: &mut Gameplay) {
+	let destroyed = remove_where(&mut gameplay.world.breakables, |breakables, id| {
+		breakables.health[id].should_kill()
+	});
+	if destroyed.is_empty() {
+		return;
+	}
+
+	// Lua lets go of the destroyed breakables first, so they can be changed without being copied
+	lua().remove_app_data::<Arc<Broken>>();
+	let broken = Arc::make_mut(&mut gameplay.save.broken);
+
+	for breakable in destroyed {
+		info!("Breakable {} destroyed", breakable.index);
+
+		broken
+			.entry(gameplay.current_map.clone())
+			.or_default()
+			.insert(breakable.index);
+	}
+
+	share_broken(&gameplay.save.broken);
+}
@@ -1,19 +1,42 @@
-use mlua::{FromLua, UserData};
-use raywoke::prelude::*;
+use macroquad::math::DVec2;
+use mlua::{FromLua, LuaSerdeExt, UserData, UserDataMethods};
 use rustc_hash::FxHashMap;
+use serde::Deserialize;
 use stecs::prelude::*;
 
 use super::{
 	Gameplay,
 	ecs::{
 		health::Health,
-		obj::Obj,
+		obj::{Obj, hitbox::Hitbox},
 		sprite::{Frames, Rotation, Sprite},
 	},
-	paused::Paused,
+	input::input,
 };
 
-use crate::utils::{mouse_pos, resources::textures::access_image, smart_time, tup_vec::Tup64};
+use crate::utils::{
+	TICK,
+	resources::{
+		audio::play_random_sound, damage::access_damage_type, parries::access_parry,
+		textures::access_image,
+	},
+};
+
+use feedback::{CombatEvent, Target};
+use projectile::{Projectile, move_projectile};
+use script::{AttackScript, script_hit, script_update};
+
+pub mod feedback;
+pub mod pattern;
+pub mod projectile;
+pub mod script;
+
+/// How hard attacks push what they hit, per point of damage
+const KNOCKBACK: f64 = 0.5;
+/// How much more parried attacks push what they hit
+const PARRY_KNOCKBACK: f64 = 3.;
+/// How physical attacks parry, unless they are given another way
+const PHYSICAL_PARRY: &str = "default:physical";
 
 #[derive(Clone, SplitFields)]
 pub struct Attack {
@@ -23,10 +46,32 @@ pub struct Attack {
 	pub is_parried: bool,
 
 	pub atk_type: AttackType,
-	damage: f64,
+	pub(super) damage: f64,
+	/// How long the attack has existed for
+	pub(super) age: f64,
+	/// The key of the attack's [`DamageType`](crate::cores::damagetype::DamageType),
+	/// which starts out based upon the attack's [`AttackType`]
+	pub damage_type: String,
 	lifetime: f64,
 
 	pub sprite: Sprite,
+	/// The shape used to check what the attack hits
+	pub hitbox: Hitbox,
+	/// The keys of the status effects applied to what the attack hits
+	pub effects: Vec<String>,
+	/// How much gun energy the player gains when the attack hits an enemy
+	pub energy: f64,
+	/// The ids of the [`Health`]s that the attack has damaged, which it won't damage again
+	pub(super) hit_targets: Vec<u64>,
+	/// Identifies the pattern the attack was fired in, if it was,
+	/// so that the invincibility frames caused by one of its attacks don't block the others
+	pub(super) group: Option<u64>,
+	/// How the attack moves, which is only used by projectiles
+	pub projectile: Projectile,
+	/// The key of the [`ParryType`](crate::cores::parrytype::ParryType) used to parry other attacks, if it can
+	pub parry: Option<String>,
+	/// Optionally controls the attack's behavior, alongside its [`AttackType`]
+	pub script: Option<AttackScript>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -35,7 +80,7 @@ pub enum Owner {
 	Enemy,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Eq, Hash, Deserialize)]
 pub enum AttackType {
 	Physical,
 	Burst,
@@ -43,7 +88,29 @@ pub enum AttackType {
 	Hitscan,
 }
 
+impl AttackType {
+	/// The hitbox that attacks of this type are created with
+	pub fn hitbox(&self) -> Hitbox {
+		match self {
+			AttackType::Hitscan => Hitbox::Capsule,
+			_ => Hitbox::Circle,
+		}
+	}
+}
+
 impl Attack {
+	/// Creates an attack of the provided type
+	pub fn new(atk_type: &AttackType, obj: Obj, damage: f64, owner: Owner, key: &str) -> Attack {
+		let new = match atk_type {
+			AttackType::Physical => Attack::new_physical,
+			AttackType::Burst => Attack::new_burst,
+			AttackType::Projectile => Attack::new_projectile,
+			AttackType::Hitscan => Attack::new_hitscan,
+		};
+
+		new(obj, damage, owner, key)
+	}
+
 	pub fn new_physical(obj: Obj, damage: f64, owner: Owner, key: &str) -> Attack {
 		Attack {
 			obj,
@@ -53,6 +120,8 @@ impl Attack {
 
 			atk_type: AttackType::Physical,
 			damage,
+			age: 0.,
+			damage_type: "default:slash".to_owned(),
 			lifetime: 2.,
 
 			sprite: Sprite::new(
@@ -62,6 +131,14 @@ impl Attack {
 				Frames::new_attack(),
 				FxHashMap::default(),
 			),
+			hitbox: Hitbox::Circle,
+			effects: Vec::new(),
+			energy: 0.,
+			hit_targets: Vec::new(),
+			group: None,
+			projectile: Projectile::default(),
+			parry: Some(PHYSICAL_PARRY.to_owned()),
+			script: None,
 		}
 	}
 
@@ -74,6 +151,8 @@ impl Attack {
 
 			atk_type: AttackType::Burst,
 			damage,
+			age: 0.,
+			damage_type: "default:blunt".to_owned(),
 			lifetime: 12.,
 
 			sprite: Sprite::new(
@@ -83,6 +162,14 @@ impl Attack {
 				Frames::new_attack(),
 				FxHashMap::default(),
 			),
+			hitbox: Hitbox::Circle,
+			effects: Vec::new(),
+			energy: 0.,
+			hit_targets: Vec::new(),
+			group: None,
+			projectile: Projectile::default(),
+			parry: None,
+			script: None,
 		}
 	}
 
@@ -95,6 +182,8 @@ impl Attack {
 
 			atk_type: AttackType::Projectile,
 			damage,
+			age: 0.,
+			damage_type: "default:pierce".to_owned(),
 			lifetime: 1.,
 
 			sprite: Sprite::new(
@@ -104,6 +193,14 @@ impl Attack {
 				Frames::new_static(),
 				FxHashMap::default(),
 			),
+			hitbox: Hitbox::Circle,
+			effects: Vec::new(),
+			energy: 0.,
+			hit_targets: Vec::new(),
+			group: None,
+			projectile: Projectile::default(),
+			parry: None,
+			script: None,
 		}
 	}
 
@@ -116,6 +213,8 @@ impl Attack {
 
 			atk_type: AttackType::Hitscan,
 			damage,
+			age: 0.,
+			damage_type: "default:radio".to_owned(),
 			lifetime: 8.,
 
 			sprite: Sprite::new(
@@ -125,11 +224,38 @@ impl Attack {
 				Frames::new_static(),
 				FxHashMap::default(),
 			),
+			hitbox: Hitbox::Capsule,
+			effects: Vec::new(),
+			energy: 0.,
+			hit_targets: Vec::new(),
+			group: None,
+			projectile: Projectile::default(),
+			parry: None,
+			script: None,
 		}
 	}
 }
 
-impl UserData for Attack {}
+impl UserData for Attack {
+	fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+		methods.add_method_mut("add_effect", |_, atk, key: String| {
+			atk.effects.push(key);
+			Ok(())
+		});
+		methods.add_method_mut("set_parry", |_, atk, key: Option<String>| {
+			atk.parry = key;
+			Ok(())
+		});
+		methods.add_method_mut("set_projectile", |_, atk, projectile: Projectile| {
+			atk.projectile = projectile;
+			Ok(())
+		});
+		methods.add_method_mut("set_hitbox", |lua, atk, hitbox: mlua::Value| {
+			atk.hitbox = lua.from_value(hitbox)?;
+			Ok(())
+		});
+	}
+}
 
 impl FromLua for Attack {
 	fn from_lua(value: mlua::Value, _: &mlua::Lua) -> mlua::Result<Self> {
@@ -146,57 +272,131 @@ impl FromLua for Attack {
 }
 
 pub fn handle_combat(gameplay: &mut Gameplay) {
-	try_parry(gameplay);
+	let mut energy = try_parry(gameplay);
+
+	let player_pos = gameplay.world.player.obj.first().map(|obj| obj.pos);
+	let player_targets: Vec<DVec2> = query!(gameplay.world.enemies, (&obj))
+		.map(|obj| obj.pos)
+		.collect();
+	let enemy_targets: Vec<DVec2> = player_pos.into_iter().collect();
 
 	for (_, mut atk) in gameplay.world.attacks.iter_mut() {
 		atk.sprite.update(*atk.obj);
+		script_update(&mut atk);
+		*atk.age += TICK;
 
 		// Handling the lifetime and movement of attacks
 		if *atk.atk_type == AttackType::Projectile {
-			let new_pos = atk.obj.pos.move_towards(atk.obj.target, smart_time() * 5.);
-			atk.obj.try_move(&new_pos, &gameplay.current_map);
-
-			if atk.obj.pos != new_pos {
-				*atk.lifetime = 0.;
-			}
+			let (targets, owner) = match atk.owner {
+				Owner::Player => (&player_targets, player_pos),
+				Owner::Enemy => (&enemy_targets, None),
+			};
+			move_projectile(
+				&mut atk,
+				targets,
+				owner,
+				&gameplay.current_map,
+				&gameplay.save.broken,
+			);
 		} else {
-			*atk.lifetime -= smart_time();
+			*atk.lifetime -= TICK;
 		}
 
-		let func = match atk.atk_type {
+		let attack_type = match atk.atk_type {
 			AttackType::Physical => attack_physical,
 			AttackType::Burst => attack_burst,
 			AttackType::Projectile => attack_projectile,
 			AttackType::Hitscan => attack_hitscan,
 		};
+		// Each target is only damaged once by the same attack, even after its invincibility frames end
+		let func = |obj: &mut Obj, hp: &mut Health, hitbox: &Hitbox, atk: &mut AttackRefMut| {
+			if atk.hit_targets.contains(&hp.id) {
+				return None;
+			}
+			let damage = attack_type(obj, hp, hitbox, atk)?;
+			atk.hit_targets.push(hp.id);
+
+			Some(damage)
+		};
+
+		// Hits are sent as events, so that feedback for them can be given separately
+		let mut hit = |target: Target, pos: DVec2, damage: f64, base_damage: f64| {
+			gameplay.feedback.events.push(CombatEvent::Hit {
+				target,
+				pos,
+				damage,
+				critical: damage > base_damage,
+			});
+		};
 
 		macro_rules! attack {
-			($field:expr) => {
-				for (obj, hp, sprite) in query!($field, (&mut obj, &mut health, &mut sprite)) {
-					func(obj, hp, sprite, &mut atk)
+			($field:expr, $target:path) => {
+				for (id, target) in $field.iter_mut() {
+					if let Some(damage) = func(target.obj, target.health, target.hitbox, &mut atk) {
+						energy += *atk.energy;
+						for key in atk.effects.iter() {
+							target.effects.apply(key);
+						}
+						hit($target(id), target.obj.pos, damage, *atk.damage);
+						script_hit(&mut atk, target.obj.pos, damage);
+					}
 				}
 			};
 		}
 
 		match atk.owner {
-			Owner::Player => attack!(gameplay.world.enemies),
-			Owner::Enemy => attack!(gameplay.world.player),
+			Owner::Player => attack!(gameplay.world.enemies, Target::Enemy),
+			Owner::Enemy => attack!(gameplay.world.player, Target::Player),
 		}
+
+		// Breakables can be destroyed by anyone, as long as the attack is of the right type
+		for (id, breakable) in gameplay.world.breakables.iter_mut() {
+			if breakable.weak_to.contains(atk.atk_type)
+				&& let Some(damage) =
+					func(breakable.obj, breakable.health, breakable.hitbox, &mut atk)
+			{
+				hit(
+					Target::Breakable(id),
+					breakable.obj.pos,
+					damage,
+					*atk.damage,
+				);
+				script_hit(&mut atk, breakable.obj.pos, damage);
+			}
+		}
+	}
+
+	for inventory in query!(gameplay.world.player, (&mut inventory)) {
+		inventory.gain_energy(energy);
 	}
 }
 
-fn attack_physical(obj: &mut Obj, hp: &mut Health, sprite: &mut Sprite, atk: &mut AttackRefMut) {
-	if *atk.lifetime >= 0. && obj.is_touching(atk.obj) {
-		hp.damage(*atk.damage);
-		sprite.shake();
+fn attack_physical(
+	obj: &mut Obj,
+	hp: &mut Health,
+	hitbox: &Hitbox,
+	atk: &mut AttackRefMut,
+) -> Option<f64> {
+	if *atk.lifetime < 0. || !atk.hitbox.overlaps(atk.obj, hitbox, obj) {
+		return None;
+	}
+	let damage = hp.damage(*atk.damage, atk.damage_type, *atk.group)?;
 
-		if *atk.is_parried {
-			obj.stunned = 40.;
-		}
+	if *atk.is_parried {
+		knockback(obj, atk.obj.pos, damage * PARRY_KNOCKBACK, atk.damage_type);
+	} else {
+		knockback(obj, atk.obj.pos, damage, atk.damage_type);
 	}
+
+	Some(damage)
 }
 
-fn attack_burst(obj: &mut Obj, hp: &mut Health, sprite: &mut Sprite, atk: &mut AttackRefMut) {
+fn attack_burst(
+	obj: &mut Obj,
+	hp: &mut Health,
+	hitbox: &Hitbox,
+	atk: &mut AttackRefMut,
+) -> Option<f64> {
 	// Returns the attack but with double the size
 	let double_size = |obj: &Obj| {
 		let mut to_return = *obj;
@@ -205,40 +405,74 @@ fn attack_burst(obj: &mut Obj, hp: &mut Health, sprite: &mut Sprite, atk: &mut A
 		to_return
 	};
 
-	if *atk.lifetime >= 0. && obj.is_touching(&double_size(atk.obj)) {
-		sprite.shake();
-		hp.damage(*atk.damage * (obj.pos.distance(atk.obj.pos) / (atk.obj.size * 2.)));
+	if *atk.lifetime < 0. || !atk.hitbox.overlaps(&double_size(atk.obj), hitbox, obj) {
+		return None;
 	}
+	let damage = *atk.damage * (obj.pos.distance(atk.obj.pos) / (atk.obj.size * 2.));
+	let damage = hp.damage(damage, atk.damage_type, *atk.group)?;
+
+	knockback(obj, atk.obj.pos, damage, atk.damage_type);
+
+	Some(damage)
 }
 
-fn attack_projectile(obj: &mut Obj, hp: &mut Health, sprite: &mut Sprite, atk: &mut AttackRefMut) {
-	if obj.is_touching(atk.obj) {
-		sprite.shake();
-		hp.damage(*atk.damage);
+fn attack_projectile(
+	obj: &mut Obj,
+	hp: &mut Health,
+	hitbox: &Hitbox,
+	atk: &mut AttackRefMut,
+) -> Option<f64> {
+	if !atk.hitbox.overlaps(atk.obj, hitbox, obj) {
+		return None;
+	}
+	let damage = hp.damage(*atk.damage, atk.damage_type, *atk.group);
+	if atk.projectile.hit(damage.is_some()) {
 		*atk.lifetime = 0.;
 	}
+	let damage = damage?;
+
+	knockback(obj, atk.obj.pos, damage, atk.damage_type);
+
+	Some(damage)
 }
 
-fn attack_hitscan(obj: &mut Obj, hp: &mut Health, sprite: &mut Sprite, atk: &mut AttackRefMut) {
-	if cast_wide(
-		&Ray::new(atk.obj.pos.tup64(), atk.obj.target.tup64()),
-		&obj.to_barriers(),
-	)
-	.is_ok()
-	{
-		sprite.shake();
-		hp.damage(*atk.damage);
+fn attack_hitscan(
+	obj: &mut Obj,
+	hp: &mut Health,
+	hitbox: &Hitbox,
+	atk: &mut AttackRefMut,
+) -> Option<f64> {
+	if !atk.hitbox.overlaps(atk.obj, hitbox, obj) {
+		return None;
 	}
+	let damage = hp.damage(*atk.damage, atk.damage_type, *atk.group)?;
+
+	knockback(obj, atk.obj.pos, damage, atk.damage_type);
+
+	Some(damage)
+}
+
+/// Pushes the Obj away from the provided position, based upon the damage it took
+fn knockback(obj: &mut Obj, from: DVec2, damage: f64, damage_type: &str) {
+	let knockback = access_damage_type(damage_type).map_or(1., |damage_type| damage_type.knockback);
+	obj.impulse((obj.pos - from).normalize_or_zero() * damage * KNOCKBACK * knockback);
 }
 
-/// Attempts to parry attacks
-fn try_parry(gameplay: &mut Gameplay) {
+/// Attempts to parry attacks, returning how much gun energy the player gained from parrying
+fn try_parry(gameplay: &mut Gameplay) -> f64 {
 	let attack_ids: Vec<usize> = gameplay.world.attacks.ids().collect();
+	let mut energy = 0.;
 
 	for i in attack_ids.iter().rev() {
 		let atk_1 = gameplay.world.attacks.get(*i).expect("Attack should exist");
 
-		if *atk_1.atk_type != AttackType::Physical || *atk_1.is_parried {
+		if *atk_1.is_parried {
+			continue;
+		}
+		let Some(parry) = atk_1.parry.as_deref().and_then(access_parry) else {
+			continue;
+		};
+		if *atk_1.age > parry.window {
 			continue;
 		}
 
@@ -249,32 +483,35 @@ fn try_parry(gameplay: &mut Gameplay) {
 
 			let atk_2 = gameplay.world.attacks.get(*j).expect("Attack should exist");
 
-			if !atk_2.obj.is_touching(atk_1.obj) || *atk_2.is_parried {
+			if !atk_1.hitbox.overlaps(atk_1.obj, atk_2.hitbox, atk_2.obj) || *atk_2.is_parried {
+				continue;
+			}
+			let Some(result) = parry.parries.get(atk_2.atk_type) else {
+				continue;
+			};
+			if atk_1.owner == atk_2.owner && !result.same_owner {
 				continue;
 			}
 
-			// First match block, to check if attack has parry interactions.
-			match atk_2.atk_type {
-				// Physical attacks should not be able to parry themselves
-				AttackType::Physical => {
-					if atk_1.owner == atk_2.owner {
-						continue;
-					}
-				}
-				// Burst and hitscan attacks cannot be parried
-				AttackType::Burst | AttackType::Hitscan => continue,
-
-				AttackType::Projectile => (),
+			// Only parrying the attacks of enemies gives energy, so it can't be gained from the player's own attacks
+			if *atk_1.owner == Owner::Player && *atk_2.owner == Owner::Enemy {
+				energy += parry.energy;
 			}
 
-			gameplay.paused = Paused::Hitstop(16.);
+			gameplay.feedback.events.push(CombatEvent::Parry {
+				pos: atk_2.obj.pos,
+				hitstop: parry.hitstop,
+			});
+			if !parry.sounds.is_empty() {
+				play_random_sound(&parry.sounds);
+			}
 
 			let atk_1 = &mut gameplay.world.attacks.get_mut(*i).unwrap();
-			*atk_1.lifetime += smart_time();
+			*atk_1.lifetime += TICK;
 			*atk_1.is_parried = true;
 
 			let new_owner = atk_1.owner.clone();
-			let new_damage = *atk_1.damage;
+			let new_damage = *atk_1.damage * parry.damage_bonus;
 			let new_target = atk_1.obj.target;
 
 			let atk_2 = &mut gameplay.world.attacks.get_mut(*j).unwrap();
@@ -282,25 +519,24 @@ fn try_parry(gameplay: &mut Gameplay) {
 			*atk_2.damage += new_damage;
 			*atk_2.is_parried = true;
 
-			// Second match block, to check what those parry interactions should be
-			match atk_2.atk_type {
-				AttackType::Physical => *atk_2.lifetime += smart_time(),
-
-				AttackType::Projectile => {
-					*atk_2.lifetime = 6.;
-					*atk_2.atk_type = AttackType::Hitscan;
-
-					atk_2
-						.sprite
-						.set_img(access_image("default:attacks/hitscan-enemy").clone());
-					atk_2.obj.target = 999.
-						* match atk_2.owner {
-							Owner::Player => mouse_pos(),
-							Owner::Enemy => new_target,
-						};
-				}
-
-				_ => unreachable!("How did a non-parryable attack end up here?"),
+			*atk_2.lifetime = result.lifetime.unwrap_or(*atk_2.lifetime + TICK);
+			if let Some(atk_type) = &result.becomes {
+				*atk_2.atk_type = atk_type.clone();
+				*atk_2.hitbox = atk_type.hitbox();
+			}
+			if let Some(hitbox) = &result.hitbox {
+				*atk_2.hitbox = hitbox.clone();
+			}
+			if let Some(key) = &result.sprite {
+				atk_2.sprite.set_img(access_image(key).clone());
+			}
+			atk_2.effects.extend(result.effects.iter().cloned());
+			if result.redirect {
+				atk_2.obj.target = 999.
+					* match atk_2.owner {
+						Owner::Player => input().mouse_pos(),
+						Owner::Enemy => new_target,
+					};
 			}
 
 			// If this were a `continue`, this code would not be valid,
@@ -309,4 +545,6 @@ fn try_parry(gameplay: &mut Gameplay) {
 			break;
 		}
 	}
+
+	energy
 }
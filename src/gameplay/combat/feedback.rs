@@ -0,0 +1,159 @@
+use macroquad::{
+	color::{Color, GOLD, RED, SKYBLUE, WHITE},
+	math::DVec2,
+};
+
+use crate::{
+	gameplay::{Gameplay, paused::Paused},
+	utils::{TICK, resources::config::access_config},
+};
+
+/// Hits dealing at least this much damage cause hitstop
+const HEAVY_HIT: f64 = 15.;
+/// How long the game pauses for on heavy hits
+const HEAVY_HITSTOP: f64 = 6.;
+/// How long damage numbers are shown for
+const NUMBER_TIME: f64 = 40.;
+/// How fast damage numbers float upwards
+const NUMBER_SPEED: f64 = 0.6;
+/// How long flourishes are shown for
+const FLOURISH_TIME: f64 = 16.;
+
+/// Something that happened during combat, which the player is given feedback for
+pub enum CombatEvent {
+	/// Something was damaged by an attack
+	Hit {
+		target: Target,
+		pos: DVec2,
+		damage: f64,
+		/// If true, the damage was increased by a weakness or vulnerability
+		critical: bool,
+	},
+	/// An attack was parried, pausing the game for the provided hitstop
+	Parry { pos: DVec2, hitstop: f64 },
+}
+
+/// What was hit by an attack, with the id of it within its storage
+#[derive(Clone, Copy)]
+pub enum Target {
+	Player(usize),
+	Enemy(usize),
+	Breakable(usize),
+}
+
+/// The combat events that happened this tick, and the feedback currently being shown for past ones
+#[derive(Default)]
+pub struct Feedback {
+	pub events: Vec<CombatEvent>,
+
+	pub numbers: Vec<DamageNumber>,
+	pub flourishes: Vec<Flourish>,
+}
+
+/// A number showing how much damage was dealt, which floats upwards
+pub struct DamageNumber {
+	pub pos: DVec2,
+	pub damage: f64,
+	pub color: Color,
+	pub remaining: f64,
+}
+
+/// A ring that bursts outwards from a critical hit or parry
+pub struct Flourish {
+	pub pos: DVec2,
+	pub color: Color,
+	pub remaining: f64,
+}
+
+impl Flourish {
+	/// How far the flourish has progressed, from `0` to `1`
+	pub fn progress(&self) -> f64 {
+		1. - self.remaining / FLOURISH_TIME
+	}
+}
+
+impl DamageNumber {
+	/// How visible the number is, as it fades out near the end of its life
+	pub fn alpha(&self) -> f32 {
+		(self.remaining / NUMBER_TIME * 2.).min(1.) as f32
+	}
+}
+
+/// Gives feedback for this tick's combat events, based upon which kinds of feedback are enabled
+pub fn handle_feedback(gameplay: &mut Gameplay) {
+	let config = access_config();
+	let config = &config.feedback;
+	let feedback = &mut gameplay.feedback;
+
+	for number in &mut feedback.numbers {
+		number.pos.y -= NUMBER_SPEED * TICK;
+		number.remaining -= TICK;
+	}
+	feedback.numbers.retain(|number| number.remaining > 0.);
+
+	for flourish in &mut feedback.flourishes {
+		flourish.remaining -= TICK;
+	}
+	feedback
+		.flourishes
+		.retain(|flourish| flourish.remaining > 0.);
+
+	for event in std::mem::take(&mut feedback.events) {
+		match event {
+			CombatEvent::Hit {
+				target,
+				pos,
+				damage,
+				critical,
+			} => {
+				let sprite = match target {
+					Target::Player(id) => gameplay.world.player.sprite.get_mut(id),
+					Target::Enemy(id) => gameplay.world.enemies.sprite.get_mut(id),
+					Target::Breakable(id) => gameplay.world.breakables.sprite.get_mut(id),
+				};
+				if let Some(sprite) = sprite {
+					sprite.shake();
+					if config.hit_flash {
+						sprite.flash();
+					}
+				}
+
+				if config.damage_numbers {
+					feedback.numbers.push(DamageNumber {
+						pos,
+						damage,
+						color: match (target, critical) {
+							(Target::Player(_), _) => RED,
+							(_, true) => GOLD,
+							(_, false) => WHITE,
+						},
+						remaining: NUMBER_TIME,
+					});
+				}
+				if config.flourishes && critical {
+					feedback.flourishes.push(Flourish {
+						pos,
+						color: GOLD,
+						remaining: FLOURISH_TIME,
+					});
+				}
+				if config.hitstop && damage >= HEAVY_HIT && !gameplay.paused.is_paused() {
+					gameplay.paused = Paused::Hitstop(HEAVY_HITSTOP);
+				}
+			}
+
+			CombatEvent::Parry { pos, hitstop } => {
+				if config.flourishes {
+					feedback.flourishes.push(Flourish {
+						pos,
+						color: SKYBLUE,
+						remaining: FLOURISH_TIME,
+					});
+				}
+				if config.hitstop && !gameplay.paused.is_paused() {
+					gameplay.paused = Paused::Hitstop(hitstop);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,102 @@
+use std::{
+	f64::consts::TAU,
+	num::NonZeroU32,
+	sync::atomic::{AtomicU64, Ordering},
+};
+
+use macroquad::{math::DVec2, rand::gen_range};
+use mlua::{FromLua, LuaSerdeExt};
+use serde::Deserialize;
+use stecs::prelude::*;
+use tracing::error;
+
+use crate::{gameplay::Gameplay, utils::TICK};
+
+use super::Attack;
+
+/// The group given to the attacks of the next pattern that is emitted
+static NEXT_GROUP: AtomicU64 = AtomicU64::new(0);
+
+/// A way of firing several copies of an attack at once, each aimed in its own direction
+#[derive(Clone, Deserialize)]
+pub struct Pattern {
+	pub shape: PatternShape,
+	/// How many attacks are fired, which must be at least one
+	pub count: NonZeroU32,
+	/// How much the speed of each attack can randomly differ, as a fraction of its speed
+	#[serde(default)]
+	pub speed_variance: f64,
+	/// How long to wait between firing each attack
+	#[serde(default)]
+	pub stagger: f64,
+}
+
+/// How the attacks of a [`Pattern`] are aimed, relative to the original attack's target
+#[derive(Clone, Deserialize)]
+pub enum PatternShape {
+	/// Spread evenly across the provided angle in radians, centered on the target
+	Spread(f64),
+	/// Spread evenly around a full circle, starting at the target
+	Ring,
+	/// Each turned by the provided angle in radians from the last, starting at the target
+	Spiral(f64),
+}
+
+impl Pattern {
+	/// Creates the attacks of the pattern from the provided attack,
+	/// alongside how long each one should wait before being spawned
+	pub fn emit(&self, atk: &Attack) -> Vec<(f64, Attack)> {
+		let count = self.count.get();
+		let group = NEXT_GROUP.fetch_add(1, Ordering::Relaxed);
+
+		(0..count)
+			.map(|i| {
+				let angle = match self.shape {
+					PatternShape::Spread(_) if count == 1 => 0.,
+					PatternShape::Spread(spread) => {
+						spread * (f64::from(i) / f64::from(count - 1) - 0.5)
+					}
+					PatternShape::Ring => TAU * f64::from(i) / f64::from(count),
+					PatternShape::Spiral(turn) => turn * f64::from(i),
+				};
+
+				let mut copy = atk.clone();
+				copy.group = Some(group);
+				copy.obj.target =
+					copy.obj.pos + DVec2::from_angle(angle).rotate(copy.obj.target - copy.obj.pos);
+				if self.speed_variance > 0. {
+					copy.obj.speed *= 1. + gen_range(-self.speed_variance, self.speed_variance);
+				}
+
+				// Scripts are restarted so that each attack has its own state
+				if let Some(script) = &atk.script {
+					copy.script = script
+						.restart()
+						.inspect_err(|e| error!("Failed to restart attack script: {e}"))
+						.ok();
+				}
+
+				(self.stagger * f64::from(i), copy)
+			})
+			.collect()
+	}
+}
+
+impl FromLua for Pattern {
+	fn from_lua(value: mlua::Value, lua: &mlua::Lua) -> mlua::Result<Self> {
+		lua.from_value(value)
+	}
+}
+
+/// Spawns any queued attacks that are done waiting
+pub fn spawn_queued_attacks(gameplay: &mut Gameplay) {
+	let world = &mut gameplay.world;
+
+	for (delay, atk) in std::mem::take(&mut world.queued_attacks) {
+		if delay <= 0. {
+			world.attacks.insert(atk);
+		} else {
+			world.queued_attacks.push((delay - TICK, atk));
+		}
+	}
+}
@@ -0,0 +1,127 @@
+use macroquad::math::DVec2;
+use mlua::{FromLua, LuaSerdeExt};
+use serde::Deserialize;
+
+use crate::{gameplay::breakable::Broken, utils::TICK};
+
+use super::AttackRefMut;
+
+/// How far projectiles travel each tick, before being multiplied by their speed
+const PROJECTILE_SPEED: f64 = 5.;
+/// How far away a projectile can be from its owner to be caught when returning
+const CATCH_DISTANCE: f64 = 10.;
+/// How far away targets can be for homing projectiles to turn towards them
+const HOMING_RANGE: f64 = 200.;
+/// How far away a projectile's target is put when it changes direction
+const TARGET_DISTANCE: f64 = 999.;
+
+/// How a projectile behaves as it flies, on top of moving in a straight line
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Projectile {
+	/// How many targets the projectile can pass through after damaging them,
+	/// as it is removed by the next thing it hits once none are left
+	pub pierce: u32,
+	/// How many more times the projectile can bounce off of barriers before it is removed
+	pub bounces: u32,
+	/// How far the projectile flies before returning to its owner, if it returns
+	pub returns: Option<f64>,
+	/// How quickly the projectile turns towards the closest thing it can hit, in radians per tick
+	pub homing: f64,
+
+	/// Where the projectile was first moved from, which is returned to if the owner is unknown
+	#[serde(skip)]
+	origin: Option<DVec2>,
+	#[serde(skip)]
+	travelled: f64,
+	#[serde(skip)]
+	returning: bool,
+}
+
+impl Projectile {
+	/// Checks if a projectile that hit something should be removed,
+	/// using up one of its pierces if it was able to deal damage
+	pub fn hit(&mut self, dealt_damage: bool) -> bool {
+		if self.pierce == 0 {
+			return true;
+		}
+		if dealt_damage {
+			self.pierce -= 1;
+		}
+
+		false
+	}
+}
+
+impl FromLua for Projectile {
+	fn from_lua(value: mlua::Value, lua: &mlua::Lua) -> mlua::Result<Self> {
+		lua.from_value(value)
+	}
+}
+
+/// Moves the projectile towards its target, handling its homing, bouncing and returning.
+///
+/// `targets` are the positions of what the projectile can hit, and `owner` is the position
+/// of whatever fired it, if it is known.
+pub fn move_projectile(
+	atk: &mut AttackRefMut,
+	targets: &[DVec2],
+	owner: Option<DVec2>,
+	current_map: &str,
+	broken: &Broken,
+) {
+	let pos = atk.obj.pos;
+	let projectile = &mut *atk.projectile;
+	let origin = *projectile.origin.get_or_insert(pos);
+
+	let mut dir = (atk.obj.target - pos).normalize_or_zero();
+	let was_returning = projectile.returning;
+
+	if projectile.returning {
+		let owner = owner.unwrap_or(origin);
+		if pos.distance(owner) <= atk.obj.size + CATCH_DISTANCE {
+			*atk.lifetime = 0.;
+			return;
+		}
+
+		dir = (owner - pos).normalize_or_zero();
+	} else if projectile.homing > 0.
+		&& let Some(closest) = targets
+			.iter()
+			.filter(|target| target.distance(pos) <= HOMING_RANGE)
+			.min_by(|a, b| a.distance(pos).total_cmp(&b.distance(pos)))
+	{
+		let max_turn = projectile.homing * TICK;
+		let turn = dir.angle_between(*closest - pos).clamp(-max_turn, max_turn);
+
+		dir = DVec2::from_angle(turn).rotate(dir);
+	}
+
+	let motion = dir * PROJECTILE_SPEED * TICK * atk.obj.speed * atk.obj.speed_mult;
+	let normal = atk.obj.slide(motion, current_map, broken);
+	projectile.travelled += atk.obj.pos.distance(pos);
+
+	if let Some(normal) = normal {
+		if projectile.bounces > 0 {
+			projectile.bounces -= 1;
+			dir -= 2. * dir.dot(normal) * normal;
+		} else if projectile.returns.is_some() && !projectile.returning {
+			projectile.returning = true;
+		} else {
+			*atk.lifetime = 0.;
+			return;
+		}
+	}
+
+	if let Some(range) = projectile.returns
+		&& projectile.travelled >= range
+	{
+		projectile.returning = true;
+	}
+	// Targets that were hit on the way out can be hit again on the way back
+	if projectile.returning && !was_returning {
+		atk.hit_targets.clear();
+	}
+
+	atk.obj.target = atk.obj.pos + dir * TARGET_DISTANCE;
+}
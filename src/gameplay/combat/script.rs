@@ -0,0 +1,112 @@
+use macroquad::math::DVec2;
+use mlua::{Function, IntoLuaMulti, Table, Value};
+use tracing::error;
+
+use crate::{
+	cores::script::Script,
+	utils::{error::EvoidResult, lua::LuaDVec2, resources::scripts::lua},
+};
+
+use super::{AttackRefMut, Owner};
+
+/// A Lua script that controls an attack, alongside the state of that one attack.
+///
+/// The script can contain `on_spawn`, `on_update`, `on_hit` and `on_expire` functions,
+/// which are all passed the attack's state as `self`. The state contains the attack's
+/// `pos`, `target`, `size`, `damage`, `damage_type`, `lifetime` and `owner`, and any
+/// of them but `size` and `owner` can be changed to affect the attack.
+#[derive(Clone)]
+pub struct AttackScript {
+	script: Script,
+	state: Table,
+	spawned: bool,
+}
+
+impl AttackScript {
+	pub fn new(key: &str) -> EvoidResult<Self> {
+		Ok(Self {
+			script: Script::new(key)?,
+			state: lua().create_table()?,
+			spawned: false,
+		})
+	}
+
+	/// Creates a copy of the script with its own state, as cloning it would share the state
+	pub fn restart(&self) -> EvoidResult<Self> {
+		Ok(Self {
+			script: self.script.clone(),
+			state: lua().create_table()?,
+			spawned: false,
+		})
+	}
+}
+
+/// Calls `on_spawn` the first time the attack is updated, and `on_update` every time after
+pub fn script_update(atk: &mut AttackRefMut) {
+	let Some(script) = atk.script.as_mut() else {
+		return;
+	};
+
+	if script.spawned {
+		run(atk, "on_update", ());
+	} else {
+		script.spawned = true;
+		run(atk, "on_spawn", ());
+	}
+}
+
+/// Calls `on_hit` with the position of what the attack hit, and the damage it took after multipliers
+pub fn script_hit(atk: &mut AttackRefMut, pos: DVec2, damage: f64) {
+	run(atk, "on_hit", (LuaDVec2(pos), damage));
+}
+
+/// Calls `on_expire`, right before the attack is removed
+pub fn script_expire(atk: &mut AttackRefMut) {
+	run(atk, "on_expire", ());
+}
+
+/// Runs the function of the attack's script, if it has one, logging any errors
+fn run(atk: &mut AttackRefMut, name: &str, args: impl IntoLuaMulti) {
+	if let Err(e) = try_run(atk, name, args) {
+		let key = atk.script.as_ref().map_or("", |script| &script.script.name);
+		error!("Attack script {key} failed to run {name}: {e}");
+		// Scripts that fail are removed, to avoid the error being logged every tick
+		*atk.script = None;
+	}
+}
+
+fn try_run(atk: &mut AttackRefMut, name: &str, args: impl IntoLuaMulti) -> EvoidResult<()> {
+	let Some(script) = atk.script.as_ref() else {
+		return Ok(());
+	};
+	let Some(fun) = script.script.table()?.get::<Option<Function>>(name)? else {
+		return Ok(());
+	};
+	let state = script.state.clone();
+
+	state.set("pos", LuaDVec2(atk.obj.pos))?;
+	state.set("target", LuaDVec2(atk.obj.target))?;
+	state.set("size", atk.obj.size)?;
+	state.set("damage", *atk.damage)?;
+	state.set("damage_type", atk.damage_type.as_str())?;
+	state.set("lifetime", *atk.lifetime)?;
+	state.set(
+		"owner",
+		match atk.owner {
+			Owner::Player => "player",
+			Owner::Enemy => "enemy",
+		},
+	)?;
+
+	let mut args = args.into_lua_multi(&lua())?;
+	args.push_front(Value::Table(state.clone()));
+	fun.call::<()>(args)?;
+
+	atk.obj.pos = *state.get::<LuaDVec2>("pos")?;
+	atk.obj.target = *state.get::<LuaDVec2>("target")?;
+	*atk.damage = state.get("damage")?;
+	*atk.damage_type = state.get("damage_type")?;
+	*atk.lifetime = state.get("lifetime")?;
+
+	Ok(())
+}
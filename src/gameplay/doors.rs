@@ -68,22 +68,26 @@ impl Door {
 
 	/// Checks if the map should be changed, and changes it if it should
 	pub fn try_change_map(&self, gameplay: &mut Gameplay) {
+		// Doors stay locked until the current arena is completed
+		if gameplay.world.arena.is_some() {
+			return;
+		}
+
 		let Some(player) = gameplay.world.player.get_mut(0) else {
 			error!("Player not found");
 			return;
 		};
 
-		let speed = player.obj.speed + 1.;
-
-		let mut new_pos = player.obj.pos
-			+ match self.direction {
-				Direction::North => DVec2::new(0., -speed),
-				Direction::South => DVec2::new(0., speed),
-				Direction::East => DVec2::new(-speed, 0.),
-				Direction::West => DVec2::new(speed, 0.),
-			};
+		let dir = match self.direction {
+			Direction::North => DVec2::new(0., -1.),
+			Direction::South => DVec2::new(0., 1.),
+			Direction::East => DVec2::new(-1., 0.),
+			Direction::West => DVec2::new(1., 0.),
+		};
+		// The player is kept `size` away from the door, so that needs to be reached past too
+		let offset = dir * (player.obj.speed + player.obj.size + 1.);
 
-		let ray = Ray::new(player.obj.tup64(), new_pos.tup64());
+		let ray = Ray::new(player.obj.tup64(), (player.obj.pos + offset).tup64());
 
 		// The player has not touched the door, so the map should not be changed
 		if cast(&ray, &self.to_barrier()).is_err() {
@@ -103,16 +107,14 @@ impl Door {
 				return;
 			}
 
-			new_pos += match self.direction {
-				Direction::North => DVec2::new(0., -speed),
-				Direction::South => DVec2::new(0., speed),
-				Direction::East => DVec2::new(-speed, 0.),
-				Direction::West => DVec2::new(speed, 0.),
-			};
-			player.obj.pos = new_pos - self.pos + i.pos;
+			// Placing the player on the other side of the destination door
+			player.obj.pos = player.obj.pos + offset * 2. - self.pos + i.pos;
+			player.obj.prev_pos = player.obj.pos;
 
 			gameplay.current_map.clone_from(&self.dest);
-			gameplay.world.populate(&gameplay.current_map);
+			gameplay
+				.world
+				.populate(&gameplay.current_map, &gameplay.save);
 			gameplay.save.seen_maps.insert(self.dest.clone());
 			return;
 		}
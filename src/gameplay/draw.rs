@@ -1,5 +1,4 @@
 use macroquad::prelude::*;
-use std::cmp::Ordering;
 use stecs::prelude::*;
 
 use crate::{
@@ -7,11 +6,16 @@ use crate::{
 	menu::average_screen_size,
 	utils::{
 		camera_scale,
-		resources::{maps::access_map, textures::access_image},
+		resources::{config::access_config, maps::access_map, textures::access_image},
 	},
 };
 
-use super::{Gameplay, combat::AttackType, ecs::sprite::Sprite, paused::Paused};
+use super::{
+	Gameplay,
+	breakable::is_broken,
+	combat::{AttackType, feedback::Feedback},
+	ecs::sprite::Sprite,
+};
 
 use render::{draw_bar, draw_map, render_text, render_texture};
 
@@ -19,6 +23,9 @@ pub mod process;
 pub mod render;
 pub mod ui;
 
+/// The font size damage numbers are rasterized at, before being scaled down
+const FEEDBACK_FONT_SIZE: u16 = 48;
+
 pub async fn draw(gameplay: &mut Gameplay) {
 	// Draws the background
 	clear_background(Color::from_rgba(46, 34, 47, 255));
@@ -34,6 +41,7 @@ pub async fn draw(gameplay: &mut Gameplay) {
 			.obj
 			.first()
 			.expect("Player should exist")
+			.interpolated(gameplay.interpolation)
 			.pos
 			.as_vec2(),
 		..Default::default()
@@ -49,15 +57,32 @@ pub async fn draw(gameplay: &mut Gameplay) {
 	for door in &access_map(&gameplay.current_map).doors {
 		draw_bar(&door.to_barrier());
 	}
+	for (index, breakable) in access_map(&gameplay.current_map)
+		.breakables
+		.iter()
+		.enumerate()
+	{
+		if !is_broken(&gameplay.save.broken, &gameplay.current_map, index) {
+			for bar in &breakable.barriers {
+				draw_bar(bar);
+			}
+		}
+	}
 
 	render_sprites(gameplay).await;
 
+	if access_config().show_hitboxes {
+		draw_hitboxes(gameplay);
+	}
+
 	for (atk_type, sprite) in query!(gameplay.world.attacks, (&atk_type, &mut sprite)) {
 		if let AttackType::Hitscan = atk_type {
 			render_line(sprite).await;
 		}
 	}
 
+	draw_feedback(&gameplay.feedback);
+
 	set_default_camera();
 
 	// Render script errors (if any are present)
@@ -86,42 +111,96 @@ pub async fn draw(gameplay: &mut Gameplay) {
 		},
 	);
 
-	for (ui, health) in query!(gameplay.world.player, (&ui, &health)) {
+	for (ui, health, inventory, temperature) in query!(
+		gameplay.world.player,
+		(&ui, &health, &inventory, &temperature)
+	) {
 		ui.draw_hp(health);
-		ui.draw_temp(miniquad::date::now().sin().abs() * 100.); // TODO: Temperature system 
+		ui.draw_energy(inventory.energy);
+		ui.draw_temp(temperature);
 	}
 }
 
 async fn render_sprites(gameplay: &mut Gameplay) {
 	// Sorting sprites
-	let mut sprites: Vec<&mut Sprite> = vec![];
+	let mut sprites: Vec<(&mut Sprite, f64)> = vec![];
 	for (sprite, obj) in query!(
 		[
 			gameplay.world.player,
 			gameplay.world.enemies,
+			gameplay.world.corpses,
 			gameplay.world.npcs,
-			gameplay.world.attacks
+			gameplay.world.attacks,
+			gameplay.world.pickups,
+			gameplay.world.breakables
 		],
 		(&mut sprite, &obj)
 	) {
-		if gameplay.paused == Paused::None {
-			sprite.update(*obj);
-		}
-		sprites.push(sprite);
+		sprites.push((sprite, obj.interpolated(gameplay.interpolation).pos.y));
 	}
-	sprites.sort_by(|x, y| {
-		if x.obj().pos.y > y.obj().pos.y {
-			Ordering::Greater
-		} else if x.obj().pos.y < y.obj().pos.y {
-			Ordering::Less
-		} else {
-			Ordering::Equal
-		}
-	});
+	sprites.sort_by(|(_, x), (_, y)| x.total_cmp(y));
 
 	// Processing sprites
-	for sprite in &mut sprites {
-		let (texture, pos, params) = sprite.as_render_params();
-		render_texture(&texture, pos, params).await;
+	for (sprite, _) in &mut sprites {
+		let (texture, pos, params) = sprite.as_render_params(gameplay.interpolation);
+		render_texture(&texture, pos, sprite.tint, params).await;
+	}
+}
+
+/// Draws the outlines of every hitbox, for debugging
+fn draw_hitboxes(gameplay: &Gameplay) {
+	for (obj, hitbox) in query!(
+		[
+			gameplay.world.player,
+			gameplay.world.enemies,
+			gameplay.world.breakables
+		],
+		(&obj, &hitbox)
+	) {
+		hitbox.draw(&obj.interpolated(gameplay.interpolation), GREEN);
+	}
+
+	for (obj, hitbox) in query!(gameplay.world.attacks, (&obj, &hitbox)) {
+		hitbox.draw(obj, RED);
+	}
+}
+
+/// Draws the damage numbers and flourishes from combat
+fn draw_feedback(feedback: &Feedback) {
+	let screen_scale = access_config().screen_scale;
+
+	for flourish in &feedback.flourishes {
+		let progress = flourish.progress();
+		let mut color = flourish.color;
+		color.a = 1. - progress as f32;
+
+		draw_circle_lines(
+			flourish.pos.x as f32,
+			flourish.pos.y as f32,
+			(8. + progress * 24.) as f32 * screen_scale as f32,
+			screen_scale as f32,
+			color,
+		);
+	}
+
+	for number in &feedback.numbers {
+		let text = format!("{}", number.damage.round());
+		let params = TextParams {
+			font_size: FEEDBACK_FONT_SIZE,
+			font_scale: (screen_scale * 6. / f64::from(FEEDBACK_FONT_SIZE)) as f32,
+			color: Color {
+				a: number.alpha(),
+				..number.color
+			},
+			..Default::default()
+		};
+		let size = measure_text(&text, None, params.font_size, params.font_scale);
+
+		draw_text_ex(
+			&text,
+			number.pos.x as f32 - size.width / 2.,
+			number.pos.y as f32,
+			params,
+		);
 	}
 }
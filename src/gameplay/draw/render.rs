@@ -8,11 +8,16 @@ use crate::{
 };
 
 pub async fn draw_map(map: &Map) {
-	render_texture(&map.texture.clone(), dvec2(0., 0.), None).await;
+	render_texture(map.texture(), dvec2(0., 0.), WHITE, None).await;
 }
 
 /// Renders a texture based upon the screen scale
-pub async fn render_texture(texture: &Texture2D, pos: DVec2, params: Option<DrawTextureParams>) {
+pub async fn render_texture(
+	texture: &Texture2D,
+	pos: DVec2,
+	color: Color,
+	params: Option<DrawTextureParams>,
+) {
 	let screen_scale = access_config().screen_scale;
 
 	let scale = DVec2::new(
@@ -24,7 +29,7 @@ pub async fn render_texture(texture: &Texture2D, pos: DVec2, params: Option<Draw
 		texture,
 		pixel_offset(pos.x - scale.x / 2.),
 		pixel_offset(pos.y - scale.y / 2.),
-		WHITE,
+		color,
 		match params {
 			Option::Some(params) => params,
 			Option::None => DrawTextureParams {
@@ -47,10 +52,10 @@ pub async fn render_line(sprite: &mut Sprite) {
 	if sprite.rotation() != Rotation::Angle {
 		sprite.set_rotation(Rotation::Angle);
 	}
-	let (texture, _, _) = sprite.as_render_params();
+	let (texture, _, _) = sprite.as_render_params(1.);
 
 	loop {
-		render_texture(&texture, current, None).await;
+		render_texture(&texture, current, sprite.tint, None).await;
 		current = current.move_towards(target, jmp);
 
 		if current.distance(pos) > screen_size || current.distance(target) < jmp {
@@ -1,13 +1,27 @@
+use std::sync::OnceLock;
+
 use image::{DynamicImage, GenericImageView};
 use macroquad::prelude::*;
 
 use crate::{
-	gameplay::{draw::process::to_texture, ecs::health::Health},
+	gameplay::{
+		draw::process::to_texture,
+		ecs::{health::Health, temperature::Temperature},
+		player::MAX_ENERGY,
+	},
 	menu::average_screen_size,
 	utils::resources::textures::access_image,
 };
 
+/// The UI showing the player's health, gun energy and temperature.
+///
+/// Its textures are created the first time it is drawn, so that the player can exist without a window.
 pub struct PlayerUi {
+	keys: [&'static str; 6],
+	textures: OnceLock<UiTextures>,
+}
+
+struct UiTextures {
 	hp_texture: Texture2D,
 	hp_bar_texture: Texture2D,
 	hp_bar_offset: Vec2,
@@ -21,14 +35,16 @@ pub struct PlayerUi {
 	temp_bar_third_offset: Vec2,
 }
 
-impl PlayerUi {
-	pub fn new(
-		hp_texture: &str,
-		hp_bar_texture: &str,
-		temp_texture: &str,
-		temp_bar_first_texture: &str,
-		temp_bar_second_texture: &str,
-		temp_bar_third_texture: &str,
+impl UiTextures {
+	fn new(
+		[
+			hp_texture,
+			hp_bar_texture,
+			temp_texture,
+			temp_bar_first_texture,
+			temp_bar_second_texture,
+			temp_bar_third_texture,
+		]: [&str; 6],
 	) -> Self {
 		let hp_texture = to_texture(access_image(hp_texture));
 		let (hp_bar_offset, hp_bar_img) = remove_alpha(access_image(hp_bar_texture));
@@ -56,13 +72,41 @@ impl PlayerUi {
 			temp_bar_third_offset,
 		}
 	}
+}
+
+impl PlayerUi {
+	pub fn new(
+		hp_texture: &'static str,
+		hp_bar_texture: &'static str,
+		temp_texture: &'static str,
+		temp_bar_first_texture: &'static str,
+		temp_bar_second_texture: &'static str,
+		temp_bar_third_texture: &'static str,
+	) -> Self {
+		Self {
+			keys: [
+				hp_texture,
+				hp_bar_texture,
+				temp_texture,
+				temp_bar_first_texture,
+				temp_bar_second_texture,
+				temp_bar_third_texture,
+			],
+			textures: OnceLock::new(),
+		}
+	}
+
+	fn textures(&self) -> &UiTextures {
+		self.textures.get_or_init(|| UiTextures::new(self.keys))
+	}
 
 	pub fn draw_hp(&self, health: &Health) {
+		let textures = self.textures();
 		let scale = average_screen_size() / 300.;
-		let size = vec2(self.hp_texture.width(), self.hp_texture.height()) * scale;
+		let size = vec2(textures.hp_texture.width(), textures.hp_texture.height()) * scale;
 
 		draw_texture_ex2(
-			&self.hp_texture,
+			&textures.hp_texture,
 			Vec2::ZERO,
 			DrawTextureParams {
 				dest_size: Some(size),
@@ -71,8 +115,8 @@ impl PlayerUi {
 		);
 
 		draw_bar_right(
-			&self.hp_bar_texture,
-			self.hp_bar_offset,
+			&textures.hp_bar_texture,
+			textures.hp_bar_offset,
 			Vec2::ZERO,
 			scale,
 			health.max,
@@ -80,13 +124,37 @@ impl PlayerUi {
 		);
 	}
 
-	pub fn draw_temp(&self, temp: f64) {
+	/// Draws the gun energy as a bar below the health
+	pub fn draw_energy(&self, energy: f64) {
+		let textures = self.textures();
+		let scale = average_screen_size() / 300.;
+		let pos = vec2(4., textures.hp_texture.height() + 2.) * scale;
+		let size = vec2(textures.hp_texture.width() - 8., 3.) * scale;
+
+		draw_rectangle(pos.x, pos.y, size.x, size.y, Color::new(0., 0., 0., 0.5));
+		draw_rectangle(
+			pos.x,
+			pos.y,
+			size.x * (energy / MAX_ENERGY).clamp(0., 1.) as f32,
+			size.y,
+			SKYBLUE,
+		);
+	}
+
+	/// Draws the temperature, with each of the three bars filling up until its threshold is reached
+	pub fn draw_temp(&self, temperature: &Temperature) {
+		let Some(stats) = temperature.stats() else {
+			return;
+		};
+		let temp = temperature.current;
+
+		let textures = self.textures();
 		let scale = average_screen_size() / 300.;
-		let pos = vec2(screen_width() - (self.temp_texture.width() * scale), 0.);
-		let size = vec2(self.hp_texture.width(), self.hp_texture.height()) * scale;
+		let pos = vec2(screen_width() - (textures.temp_texture.width() * scale), 0.);
+		let size = vec2(textures.hp_texture.width(), textures.hp_texture.height()) * scale;
 
 		draw_texture_ex2(
-			&self.temp_texture,
+			&textures.temp_texture,
 			pos,
 			DrawTextureParams {
 				dest_size: Some(size),
@@ -95,30 +163,30 @@ impl PlayerUi {
 		);
 
 		draw_bar_right(
-			&self.temp_bar_first_texture,
-			self.temp_bar_first_offset,
+			&textures.temp_bar_first_texture,
+			textures.temp_bar_first_offset,
 			pos,
 			scale,
-			15.,
-			temp.min(15.),
+			stats.warm,
+			temp.min(stats.warm),
 		);
 
 		draw_bar_up(
-			&self.temp_bar_second_texture,
-			self.temp_bar_second_offset,
+			&textures.temp_bar_second_texture,
+			textures.temp_bar_second_offset,
 			pos,
 			scale,
-			15.,
-			temp.clamp(15., 30.) - 15.,
+			stats.hot - stats.warm,
+			temp.clamp(stats.warm, stats.hot) - stats.warm,
 		);
 
 		draw_bar_left(
-			&self.temp_bar_third_texture,
-			self.temp_bar_third_offset,
+			&textures.temp_bar_third_texture,
+			textures.temp_bar_third_offset,
 			pos,
 			scale,
-			70.,
-			(temp - 30.).max(0.),
+			stats.max - stats.hot,
+			(temp - stats.hot).max(0.),
 		);
 	}
 }
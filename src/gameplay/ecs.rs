@@ -1,25 +1,48 @@
-use stecs::prelude::*;
+use stecs::{prelude::*, storage::vec::VecFamily};
 
-use super::{combat::Attack, enemy::Enemy, npc::Npc, player::Player};
+use super::{
+	arena::ActiveArena,
+	breakable::{Breakable, is_broken},
+	combat::Attack,
+	enemy::{Corpse, Enemy},
+	npc::Npc,
+	pickup::Pickup,
+	player::Player,
+};
 
-use crate::utils::resources::maps::access_map;
+use crate::{
+	data::save::Save,
+	utils::resources::maps::{access_map, set_current_map},
+};
 
 pub mod behavior;
+pub mod bodies;
+pub mod effects;
 pub mod health;
 pub mod obj;
 pub mod sprite;
+pub mod temperature;
 
 #[derive(Default)]
 pub struct World {
 	pub player: StructOf<Vec<Player>>,
 	pub enemies: StructOf<Vec<Enemy>>,
+	/// Dead enemies that are still playing their death animation
+	pub corpses: StructOf<Vec<Corpse>>,
 	pub npcs: StructOf<Vec<Npc>>,
 	pub attacks: StructOf<Vec<Attack>>,
+	/// Attacks waiting to be spawned, alongside how much longer they have to wait
+	pub queued_attacks: Vec<(f64, Attack)>,
+	pub pickups: StructOf<Vec<Pickup>>,
+	pub breakables: StructOf<Vec<Breakable>>,
+
+	/// The arena currently being fought in, if any
+	pub arena: Option<ActiveArena>,
 }
 
 impl World {
 	/// Populates the world with content from the current map, and clears old content if it exists
-	pub fn populate(&mut self, current_map: &str) {
+	pub fn populate(&mut self, current_map: &str, save: &Save) {
 		macro_rules! clear {
 			( $( $field:expr ),+ ) => {
 				$(
@@ -31,7 +54,17 @@ impl World {
 		}
 
 		// Removing old stuff
-		clear![self.enemies, self.npcs, self.attacks];
+		clear![
+			self.enemies,
+			self.corpses,
+			self.npcs,
+			self.attacks,
+			self.pickups,
+			self.breakables
+		];
+		self.queued_attacks.clear();
+		self.arena = None;
+		set_current_map(current_map);
 
 		// Adding new stuff
 		for (enemy, pos) in &access_map(current_map).enemies {
@@ -40,5 +73,39 @@ impl World {
 		for (npc, pos) in &access_map(current_map).npcs {
 			_ = self.npcs.insert(Npc::from_type(npc, pos));
 		}
+		for (index, breakable) in access_map(current_map).breakables.iter().enumerate() {
+			if !is_broken(&save.broken, current_map, index) {
+				_ = self
+					.breakables
+					.insert(Breakable::from_type(breakable, index));
+			}
+		}
+		for (index, (item, pos)) in access_map(current_map).pickups.iter().enumerate() {
+			let save_key = format!("{current_map}/{index}");
+
+			if !save.collected_pickups.contains(&save_key) {
+				_ = self.pickups.insert(Pickup::from_type(item, pos, save_key));
+			}
+		}
 	}
 }
+
+/// Removes every entity that `should_remove` returns true for, returning the removed entities.
+///
+/// Removing an entity changes the ids of the entities after it,
+/// so every entity is checked before any are removed, and they are then removed from the last id to the first.
+pub fn remove_where<A: Archetype<VecFamily>>(
+	storage: &mut A,
+	mut should_remove: impl FnMut(&mut A, usize) -> bool,
+) -> Vec<A::Item> {
+	let ids: Vec<usize> = storage.ids().collect();
+	let ids: Vec<usize> = ids
+		.into_iter()
+		.filter(|id| should_remove(storage, *id))
+		.collect();
+
+	ids.into_iter()
+		.rev()
+		.filter_map(|id| storage.remove(id))
+		.collect()
+}
@@ -4,13 +4,13 @@ use stecs::prelude::*;
 use crate::{
 	gameplay::{Gameplay, combat::Attack},
 	utils::{
+		TICK,
 		error::EvoidResult,
 		resources::{maps::access_map, scripts::lua},
-		smart_time,
 	},
 };
 
-use super::obj::Obj;
+use super::{bodies::separate_bodies, obj::Obj};
 
 pub mod goal;
 pub mod player;
@@ -20,25 +20,44 @@ pub fn handle_behavior(gameplay: &mut Gameplay) {
 
 	for (obj, controller) in query!(gameplay.world.player, (&mut obj, &mut controller)) {
 		obj_player = *obj;
-		controller.control(obj, &gameplay.current_map);
+		controller.control(obj, &gameplay.current_map, &gameplay.save.broken);
 	}
 
-	rayon::scope(|scope| {
-		for (obj, goals, sprite) in query!(
-			[gameplay.world.enemies, gameplay.world.npcs],
-			(&mut obj, &mut goals, &mut sprite)
-		) {
-			if obj.stunned > 0. {
-				obj.stunned -= smart_time();
-				goals.index = None;
-				continue;
-			}
-
-			scope.spawn(|_| {
-				goals.run_goal(obj, &obj_player, sprite, &gameplay.current_map);
-			});
+	// Goals are run in order, rather than in parallel, so that their use of the RNG is deterministic
+	for (obj, goals, sprite) in query!(
+		[gameplay.world.enemies, gameplay.world.npcs],
+		(&mut obj, &mut goals, &mut sprite)
+	) {
+		if obj.stunned > 0. {
+			obj.stunned -= TICK;
+			goals.index = None;
+			continue;
 		}
-	});
+
+		goals.run_goal(
+			obj,
+			&obj_player,
+			sprite,
+			&gameplay.current_map,
+			&gameplay.save.broken,
+		);
+	}
+
+	for obj in query!(
+		[
+			gameplay.world.player,
+			gameplay.world.enemies,
+			gameplay.world.npcs
+		],
+		(&mut obj)
+	) {
+		obj.update_velocity(&gameplay.current_map, &gameplay.save.broken);
+	}
+	separate_bodies(
+		&mut gameplay.world,
+		&gameplay.current_map,
+		&gameplay.save.broken,
+	);
 
 	if let Err(e) = retrieve_lua_attacks(gameplay) {
 		tracing::error!("{e}");
@@ -61,5 +80,21 @@ fn retrieve_lua_attacks(gameplay: &mut Gameplay) -> EvoidResult<()> {
 	}
 
 	attacks.clear()?;
+
+	// Attacks spawned in patterns can be delayed, so they are queued instead
+	let queued = lua()
+		.globals()
+		.get::<Table>("attack")?
+		.get::<Table>("_queued")?;
+
+	for entry in queued.sequence_values::<Table>() {
+		let entry = entry?;
+		gameplay
+			.world
+			.queued_attacks
+			.push((entry.get(1)?, entry.get(2)?));
+	}
+
+	queued.clear()?;
 	Ok(())
 }
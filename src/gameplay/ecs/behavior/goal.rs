@@ -5,8 +5,11 @@ use tracing::error;
 
 use crate::{
 	cores::script::Script,
-	gameplay::ecs::{obj::Obj, sprite::Sprite},
-	utils::{ImmutVec, error::EvoidResult, lua::LuaDVec2, resources::scripts::lua, smart_time},
+	gameplay::{
+		breakable::Broken,
+		ecs::{obj::Obj, sprite::Sprite},
+	},
+	utils::{ImmutVec, TICK, error::EvoidResult, lua::LuaDVec2, resources::scripts::lua},
 };
 
 pub struct Goals {
@@ -50,6 +53,7 @@ impl Goals {
 		obj_player: &Obj,
 		sprite: &mut Sprite,
 		current_map: &str,
+		broken: &Broken,
 	) {
 		// Macro to execute a function and check if it returns an error
 		macro_rules! maybe {
@@ -71,7 +75,7 @@ impl Goals {
 
 		// Updates the current goal, and checks it it should be stopped
 		if let Some(index) = self.index {
-			maybe!(self.scripts[index].update(obj_self, obj_player, sprite, current_map));
+			maybe!(self.scripts[index].update(obj_self, obj_player, sprite, current_map, broken));
 
 			if maybe!(self.scripts[index].should_stop(obj_self, obj_player)) {
 				sprite.set_default_anim();
@@ -91,6 +95,15 @@ impl Goals {
 			}
 		}
 	}
+
+	/// Runs the `on_death` function of every goal that has one
+	pub fn on_death(&mut self, obj_self: &Obj, obj_player: &Obj) {
+		for script in &mut self.scripts {
+			if let Err(e) = script.on_death(obj_self, obj_player) {
+				error!("Failed to run on_death of {}: {e}", script.name);
+			}
+		}
+	}
 }
 
 impl PartialEq for Goals {
@@ -112,6 +125,9 @@ impl Clone for Goals {
 
 impl Script {
 	fn init(&mut self, obj_self: &Obj, obj_player: &Obj) -> EvoidResult<()> {
+		// Lets scripts account for the size of their entity, such as when finding paths
+		self.table()?.set("size", obj_self.size)?;
+
 		let fun: Function = match self.table()?.get("init") {
 			Ok(fun) => fun,
 			Err(e) => match e {
@@ -127,6 +143,18 @@ impl Script {
 		))?)
 	}
 
+	fn on_death(&mut self, obj_self: &Obj, obj_player: &Obj) -> EvoidResult<()> {
+		let Some(fun) = self.table()?.get::<Option<Function>>("on_death")? else {
+			return Ok(());
+		};
+
+		Ok(fun.call((
+			self.table()?.clone(),
+			LuaDVec2(obj_self.pos),
+			LuaDVec2(obj_player.pos),
+		))?)
+	}
+
 	fn should_start(
 		&mut self,
 		obj_self: &Obj,
@@ -157,12 +185,13 @@ impl Script {
 		obj_player: &Obj,
 		sprite: &mut Sprite,
 		current_map: &str,
+		broken: &Broken,
 	) -> EvoidResult<()> {
 		let lua_current_anim =
 			lua().create_string(sprite.get_current_anim().unwrap_or_default())?;
 
 		let fun: Function = self.table()?.get("update")?;
-		let new_pos: LuaDVec2 = fun.call((
+		let (new_pos, impulse): (LuaDVec2, Option<LuaDVec2>) = fun.call((
 			self.table()?.clone(),
 			LuaDVec2(obj_self.pos),
 			LuaDVec2(obj_player.pos),
@@ -178,10 +207,15 @@ impl Script {
 		}
 
 		// Taking delta time into consideration
-		let new_pos = ((*new_pos - obj_self.pos) * smart_time()) + obj_self.pos;
+		let new_pos = ((*new_pos - obj_self.pos) * TICK) + obj_self.pos;
 
 		obj_self.update(new_pos);
-		obj_self.try_move(&new_pos, current_map);
+		obj_self.try_move(&new_pos, current_map, broken);
+
+		// Goals can optionally push the entity, by returning an impulse alongside the new position
+		if let Some(impulse) = impulse {
+			obj_self.impulse(*impulse);
+		}
 
 		Ok(())
 	}
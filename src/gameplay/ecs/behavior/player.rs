@@ -1,11 +1,17 @@
 use macroquad::math::DVec2;
 
 use crate::{
-	data::config::keymap::Key,
-	gameplay::ecs::obj::{Axis, Obj},
-	utils::{resources::config::access_config, smart_time},
+	gameplay::{
+		breakable::Broken,
+		ecs::obj::{Axis, Obj},
+		input::{Action, input},
+	},
+	utils::TICK,
 };
 
+/// How fast the player moves at the start of a dash
+const DASH_SPEED: f64 = 20.;
+
 #[derive(PartialEq, Clone, Default)]
 pub struct PlayerController {
 	pub dash_cooldown: f64,
@@ -14,7 +20,7 @@ pub struct PlayerController {
 
 impl PlayerController {
 	/// Handles player controls
-	pub fn control(&mut self, obj: &mut Obj, current_map: &str) {
+	pub fn control(&mut self, obj: &mut Obj, current_map: &str, broken: &Broken) {
 		let mut new_pos = DVec2::ZERO; // The pos to be moved to
 
 		if !self.is_dashing {
@@ -29,22 +35,17 @@ impl PlayerController {
 		axis(&obj.axis_horizontal, &mut new_pos.x);
 
 		// Dashing
-		if access_config().keymap.dash.is_down()
+		if input().is_down(Action::Dash)
 			&& self.dash_cooldown <= 0.
 			&& new_pos != DVec2::ZERO
 			&& obj.stunned < 5.
 		{
 			obj.stunned = 0.;
-			obj.speed += 12.;
+			obj.impulse(new_pos.normalize() * DASH_SPEED * obj.weight());
 			self.dash_cooldown += 45.;
 		} else if self.dash_cooldown > 0. {
-			if self.dash_cooldown > 30. {
-				self.is_dashing = true;
-				obj.speed = 12.;
-			} else {
-				self.is_dashing = false;
-			}
-			self.dash_cooldown -= smart_time();
+			self.is_dashing = self.dash_cooldown > 30.;
+			self.dash_cooldown -= TICK;
 		}
 
 		// Makes the player build up speed over time, rather than instantly starting at max speed
@@ -52,23 +53,18 @@ impl PlayerController {
 			obj.speed += obj.speed / 6.;
 		}
 
-		// Makes the player slow down if their speed is high
-		if obj.speed > 4.5 {
-			obj.speed /= 1.5;
-		}
-
 		// Makes the player extremely slow if stunned
 		// Unlike enemies, they are not completely frozen - that was the case for awhile, but it felt bad
 		if obj.stunned > 0. {
-			obj.stunned -= smart_time();
+			obj.stunned -= TICK;
 			obj.speed = 0.25;
 		}
 
 		if new_pos == DVec2::ZERO {
 			obj.speed = 1.0;
 		} else {
-			obj.update((new_pos.normalize() * smart_time()) + obj.pos);
-			obj.try_move(&obj.target.clone(), current_map);
+			obj.update((new_pos.normalize() * TICK) + obj.pos);
+			obj.try_move(&obj.target.clone(), current_map, broken);
 		}
 	}
 }
@@ -77,33 +73,25 @@ fn switch_dir_from_input(obj: &mut Obj) {
 	// Checks to see if both key1 and key2 are being held at the same time.
 	// If they are, sets the direction of the axis based upon the most recently pressed key.
 	// Otherwise, sets the direction of the axis based upon the currently pressed key.
-	fn io(key1: &Key, key2: &Key, axis: &mut Axis) {
-		if key1.is_down() && key2.is_down() {
-			if key1.is_pressed() && *axis != Axis::Negative {
+	fn io(key1: Action, key2: Action, axis: &mut Axis) {
+		let input = input();
+
+		if input.is_down(key1) && input.is_down(key2) {
+			if input.is_pressed(key1) && *axis != Axis::Negative {
 				*axis = Axis::Negative;
 			}
-			if key2.is_pressed() && *axis != Axis::Positive {
+			if input.is_pressed(key2) && *axis != Axis::Positive {
 				*axis = Axis::Positive;
 			}
-		} else if key1.is_down() {
+		} else if input.is_down(key1) {
 			*axis = Axis::Negative;
-		} else if key2.is_down() {
+		} else if input.is_down(key2) {
 			*axis = Axis::Positive;
 		} else {
 			*axis = Axis::None;
 		}
 	}
 
-	let config = access_config();
-
-	io(
-		&config.keymap.up,
-		&config.keymap.down,
-		&mut obj.axis_vertical,
-	);
-	io(
-		&config.keymap.left,
-		&config.keymap.right,
-		&mut obj.axis_horizontal,
-	);
+	io(Action::Up, Action::Down, &mut obj.axis_vertical);
+	io(Action::Left, Action::Right, &mut obj.axis_horizontal);
 }
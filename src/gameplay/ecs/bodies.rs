@@ -0,0 +1,41 @@
+use stecs::prelude::*;
+
+use crate::{gameplay::breakable::Broken, prelude::*};
+
+use super::{World, obj::Obj};
+
+/// Pushes apart any entities with mass that are overlapping.
+/// Lighter entities are pushed further than heavier ones.
+pub fn separate_bodies(world: &mut World, current_map: &str, broken: &Broken) {
+	let mut bodies: Vec<&mut Obj> = query!([world.player, world.enemies, world.npcs], (&mut obj))
+		.filter(|obj| obj.mass.is_some())
+		.collect();
+
+	for i in 0..bodies.len() {
+		let (before, after) = bodies.split_at_mut(i + 1);
+		let obj_1 = &mut *before[i];
+
+		for obj_2 in after {
+			let diff = obj_2.pos - obj_1.pos;
+			let dist = diff.length();
+			let overlap = obj_1.size + obj_2.size - dist;
+
+			if overlap <= 0. {
+				continue;
+			}
+
+			let normal = if dist > f64::EPSILON {
+				diff / dist
+			} else {
+				DVec2::X
+			};
+
+			let mass_1 = obj_1.mass.unwrap_or_default();
+			let mass_2 = obj_2.mass.unwrap_or_default();
+			let total = (mass_1 + mass_2).max(f64::EPSILON);
+
+			obj_1.slide(-normal * overlap * (mass_2 / total), current_map, broken);
+			obj_2.slide(normal * overlap * (mass_1 / total), current_map, broken);
+		}
+	}
+}
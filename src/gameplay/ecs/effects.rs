@@ -0,0 +1,86 @@
+use macroquad::color::{Color, WHITE};
+
+use crate::{
+	cores::effecttype::Stacking,
+	utils::{TICK, resources::effects::access_effect},
+};
+
+use super::{health::Health, obj::Obj, sprite::Sprite};
+
+/// The status effects currently applied to an entity
+#[derive(Default)]
+pub struct Effects {
+	pub active: Vec<Effect>,
+}
+
+/// A status effect applied to an entity
+pub struct Effect {
+	/// The key of the effect's [`EffectType`](crate::cores::effecttype::EffectType)
+	pub key: String,
+	pub remaining: f64,
+	pub stacks: u32,
+}
+
+impl Effects {
+	/// Applies the effect, following its stacking rule if it is already applied
+	pub fn apply(&mut self, key: &str) {
+		let Some(effecttype) = access_effect(key) else {
+			return;
+		};
+
+		let Some(effect) = self.active.iter_mut().find(|effect| effect.key == key) else {
+			self.active.push(Effect {
+				key: key.to_owned(),
+				remaining: effecttype.duration,
+				stacks: 1,
+			});
+			return;
+		};
+
+		match effecttype.stacking {
+			Stacking::Refresh => effect.remaining = effecttype.duration,
+			Stacking::Extend => effect.remaining += effecttype.duration,
+			Stacking::Stack(max) => {
+				effect.stacks = (effect.stacks + 1).min(max);
+				effect.remaining = effecttype.duration;
+			}
+			Stacking::Ignore => (),
+		}
+	}
+
+	/// Applies the effects to the entity, and removes those that have run out
+	pub fn update(&mut self, obj: &mut Obj, health: &mut Health, sprite: &mut Sprite) {
+		let mut speed = 1.;
+		let mut vulnerability = 1.;
+		let mut tint = WHITE;
+
+		self.active.retain_mut(|effect| {
+			let Some(effecttype) = access_effect(&effect.key) else {
+				return false;
+			};
+			let stacks = f64::from(effect.stacks);
+
+			if effecttype.damage != 0. {
+				health.drain(
+					effecttype.damage * stacks * TICK,
+					effecttype.damage_type.as_deref(),
+				);
+			}
+			speed *= effecttype.speed.powf(stacks);
+			vulnerability *= effecttype.damage_taken.powf(stacks);
+			if effecttype.stuns {
+				obj.stunned = obj.stunned.max(effect.remaining);
+			}
+
+			let (r, g, b) = effecttype.tint;
+			tint = Color::from_rgba(r, g, b, 255);
+
+			effect.remaining -= TICK;
+			effect.remaining > 0.
+		});
+
+		obj.speed_mult = speed;
+		health.vulnerability = vulnerability;
+		sprite.tint = tint;
+	}
+}
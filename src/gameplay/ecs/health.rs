@@ -1,33 +1,90 @@
-use crate::utils::smart_time;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rustc_hash::FxHashMap;
+
+use crate::utils::{TICK, resources::damage::access_damage_type};
+
+/// The id given to the next Health that is created
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
 
 pub struct Health {
+	/// Identifies what the Health belongs to, as ids within storages change when entities are removed
+	pub id: u64,
 	pub hp: f64,
 	pub max: f64,
 	i_frames: f64,
+	/// The attack group that caused the invincibility frames, whose other attacks still deal damage
+	i_frames_group: Option<u64>,
+
+	/// How much damage of each type is multiplied by, with types not listed being unchanged
+	pub multipliers: FxHashMap<String, f64>,
+	/// Multiplies all damage taken, and is set by status effects
+	pub vulnerability: f64,
+	/// Temperature gained from damage since it was last taken in by a [`Temperature`](super::temperature::Temperature)
+	pub heat_taken: f64,
 }
 
 impl Health {
 	pub fn new(hp: f64) -> Self {
 		Self {
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
 			hp,
 			max: hp,
 			i_frames: 0.,
+			i_frames_group: None,
+
+			multipliers: FxHashMap::default(),
+			vulnerability: 1.,
+			heat_taken: 0.,
 		}
 	}
 
 	pub fn update(&mut self) {
 		if self.i_frames > 0. {
-			self.i_frames -= smart_time();
+			self.i_frames -= TICK;
 		}
 	}
 
-	pub fn damage(&mut self, damage: f64) {
-		if self.i_frames <= 0. {
-			self.hp -= damage;
+	/// Damages the Health, returning the damage dealt after multipliers, if any was dealt.
+	/// Attacks in the same `group` as the one that caused the invincibility frames aren't blocked by them.
+	pub fn damage(&mut self, damage: f64, damage_type: &str, group: Option<u64>) -> Option<f64> {
+		if self.i_frames > 0. {
+			if group.is_none() || group != self.i_frames_group {
+				return None;
+			}
+		} else {
 			self.i_frames = 10.;
+			self.i_frames_group = group;
+		}
+
+		let damage = self.multiplied(damage, Some(damage_type));
+		self.hp -= damage;
+		self.heat(damage, Some(damage_type));
+
+		Some(damage)
+	}
+
+	/// Damages the Health while ignoring invincibility frames, such as for damage over time
+	pub fn drain(&mut self, damage: f64, damage_type: Option<&str>) {
+		let damage = self.multiplied(damage, damage_type);
+		self.hp -= damage;
+		self.heat(damage, damage_type);
+	}
+
+	fn heat(&mut self, damage: f64, damage_type: Option<&str>) {
+		if let Some(damage_type) = damage_type.and_then(access_damage_type) {
+			self.heat_taken += damage * damage_type.heat;
 		}
 	}
 
+	fn multiplied(&self, damage: f64, damage_type: Option<&str>) -> f64 {
+		let multiplier = damage_type
+			.and_then(|damage_type| self.multipliers.get(damage_type))
+			.unwrap_or(&1.);
+
+		damage * multiplier * self.vulnerability
+	}
+
 	pub fn should_kill(&self) -> bool {
 		self.hp <= 0.
 	}
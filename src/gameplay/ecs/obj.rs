@@ -1,16 +1,25 @@
+use collision::{closest_point, sweep_circle};
 use macroquad::math::DVec2;
-use raywoke::prelude::*;
 
 use crate::{
-	gameplay::doors::Door,
+	cores::map::grid::BarrierGrid,
+	gameplay::breakable::Broken,
 	utils::{
-		angle_between,
+		TICK,
 		resources::maps::access_map,
 		tup_vec::{DV2, Tup64},
 	},
 };
 
-const DEFAULT_BAR: Barrier = Barrier((0., 0.), (0., 0.));
+pub mod collision;
+pub mod hitbox;
+
+/// The gap kept between an Obj and the barriers it collides with
+const SKIN: f64 = 0.01;
+/// How many times an Obj can slide along barriers within a single movement
+const MAX_SLIDES: usize = 4;
+/// Velocities slower than this are treated as zero
+const MIN_VELOCITY: f64 = 0.01;
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum Axis {
@@ -22,22 +31,32 @@ pub enum Axis {
 #[derive(Clone, Copy)]
 pub struct Obj {
 	pub pos: DVec2,
+	/// The position of the Obj at the start of the current tick, used for interpolated rendering
+	pub prev_pos: DVec2,
 	pub target: DVec2,
 	pub size: f64,
+	/// How heavy the Obj is when colliding with other entities.
+	/// Objs without mass do not collide with entities at all.
+	pub mass: Option<f64>,
 
 	pub axis_horizontal: Axis,
 	pub axis_vertical: Axis,
 
 	pub speed: f64,
+	/// Multiplies the Obj's speed, and is set by status effects
+	pub speed_mult: f64,
 	pub stunned: f64,
 
-	pub depth: u8,
+	pub velocity: DVec2,
+	/// How much of the Obj's velocity remains after each tick
+	pub friction: f64,
 }
 
 impl Obj {
 	pub fn new(pos: DVec2, target: DVec2, size: f64) -> Self {
 		Self {
 			pos,
+			prev_pos: pos,
 			target,
 			size,
 
@@ -45,6 +64,15 @@ impl Obj {
 		}
 	}
 
+	/// Gets a copy of the Obj positioned between its previous and current positions.
+	/// An `alpha` of `0` is the previous position, and `1` is the current position.
+	pub fn interpolated(&self, alpha: f64) -> Self {
+		Self {
+			pos: self.prev_pos.lerp(self.pos, alpha),
+			..*self
+		}
+	}
+
 	/// Updates the Obj's target and axis
 	pub fn update(&mut self, new_target: DVec2) {
 		let calc = |diff: f64, axis: &mut Axis| {
@@ -68,85 +96,131 @@ impl Obj {
 		self.pos.distance(other.pos) <= self.size + other.size
 	}
 
-	/// Converts the Obj into two barriers, a horizontal and a vertical one
-	pub fn to_barriers(self) -> [Barrier; 2] {
-		[
-			Barrier::new(
-				(self.pos.x + self.size, self.pos.y),
-				(self.pos.x - self.size, self.pos.y),
-			),
-			Barrier::new(
-				(self.pos.x, self.pos.y + self.size),
-				(self.pos.x, self.pos.y - self.size),
-			),
-		]
+	/// Gets the mass of the Obj, defaulting to `1` for Objs without one
+	pub fn weight(&self) -> f64 {
+		self.mass.unwrap_or(1.).max(f64::EPSILON)
 	}
 
-	/// Attempts to move the Obj to its current target
-	pub fn try_move(&mut self, new_pos: &DVec2, current_map: &str) {
-		let map = access_map(current_map);
+	/// Pushes the Obj, with heavier Objs being pushed less
+	pub fn impulse(&mut self, impulse: DVec2) {
+		self.velocity += impulse / self.weight();
+	}
 
-		// Instantly returns if about to hit a door
-		let bars = &map.doors.iter().map(Door::to_barrier).collect::<Vec<_>>();
-		if cast_wide(&Ray::new(self.tup64(), new_pos.tup64()), bars).is_ok() {
+	/// Moves the Obj based upon its velocity, and then slows it down based upon its friction
+	pub fn update_velocity(&mut self, current_map: &str, broken: &Broken) {
+		if self.velocity.length() < MIN_VELOCITY {
+			self.velocity = DVec2::ZERO;
 			return;
 		}
 
-		// Handling speed
-		let new_pos = if self.depth == 0 {
-			((*new_pos - self.pos) * self.speed) + self.pos
-		} else if self.depth == 1 {
-			((*new_pos - self.pos) / 2.) + self.pos
-		} else {
-			*new_pos
-		};
+		// Stopping any velocity going into a barrier
+		if let Some(normal) = self.slide(self.velocity * TICK, current_map, broken) {
+			self.velocity -= normal * self.velocity.dot(normal).min(0.);
+		}
 
-		let mut ok = true;
-		for wall in &map.walls {
-			if cast_wide(&Ray::new(self.tup64(), new_pos.tup64()), wall).is_ok() {
-				ok = false;
+		self.velocity *= self.friction.powf(TICK);
+	}
+
+	/// Attempts to move the Obj to its current target
+	pub fn try_move(&mut self, new_pos: &DVec2, current_map: &str, broken: &Broken) {
+		self.slide(
+			(*new_pos - self.pos) * self.speed * self.speed_mult,
+			current_map,
+			broken,
+		);
+	}
+
+	/// Moves the Obj by the provided amount, sliding along any barriers in the way.
+	/// Returns the normal of the last barrier that was hit, if any were hit.
+	pub fn slide(&mut self, motion: DVec2, current_map: &str, broken: &Broken) -> Option<DVec2> {
+		self.slide_in(&access_map(current_map).grid, motion, current_map, broken)
+	}
+
+	/// Moves the Obj like [`Obj::slide`], but against the barriers within the provided grid
+	fn slide_in(
+		&mut self,
+		grid: &BarrierGrid,
+		motion: DVec2,
+		current_map: &str,
+		broken: &Broken,
+	) -> Option<DVec2> {
+		self.depenetrate(grid, current_map, broken);
+
+		let mut remaining = motion;
+		let mut last_normal = None;
+
+		for _ in 0..MAX_SLIDES {
+			if remaining.length_squared() < f64::EPSILON {
 				break;
 			}
-		}
 
-		if ok {
-			self.pos = new_pos;
-			self.depth = 0;
-			return;
-		}
+			let hit = grid
+				.query(self.pos, self.pos + remaining, self.size + SKIN)
+				.into_iter()
+				.filter(|segment| segment.is_solid(current_map, broken))
+				.filter_map(|segment| {
+					sweep_circle(
+						self.pos,
+						remaining,
+						self.size,
+						segment.bar.0.dvec2(),
+						segment.bar.1.dvec2(),
+					)
+				})
+				.min_by(|x, y| x.time.total_cmp(&y.time));
+
+			let Some(hit) = hit else {
+				self.pos += remaining;
+				break;
+			};
+
+			// Moving up to the barrier, keeping a small gap to avoid getting stuck in it
+			let travelled = remaining * hit.time;
+			self.pos += travelled + hit.normal * SKIN;
 
-		// Checking/handling recursion
-		if self.depth > 1 {
-			self.depth = 0;
-		} else {
-			self.depth += 1;
-			self.try_handle_angle(&new_pos, current_map);
+			// Sliding along the barrier with whatever movement is left
+			remaining -= travelled;
+			remaining -= hit.normal * remaining.dot(hit.normal);
+			last_normal = Some(hit.normal);
 		}
+
+		last_normal
 	}
 
-	fn try_handle_angle(&mut self, new_pos: &DVec2, current_map: &str) {
-		let mut to_check = DEFAULT_BAR;
+	/// Pushes the Obj out of any barriers it is overlapping
+	fn depenetrate(&mut self, grid: &BarrierGrid, current_map: &str, broken: &Broken) {
+		for _ in 0..MAX_SLIDES {
+			let mut pushed = false;
 
-		for wall in &access_map(current_map).walls {
-			for bar in wall {
-				if cast(&Ray::new(self.tup64(), new_pos.tup64()), bar).is_ok() {
-					to_check = bar.clone();
+			for segment in grid.query(self.pos, self.pos, self.size) {
+				if !segment.is_solid(current_map, broken) {
+					continue;
 				}
-			}
-		}
 
-		if to_check.0 == DEFAULT_BAR.0 && to_check.1 == DEFAULT_BAR.1 {
-			return;
-		}
+				let closest = closest_point(self.pos, segment.bar.0.dvec2(), segment.bar.1.dvec2());
+				let diff = self.pos - closest;
+				let dist = diff.length();
+
+				if dist >= self.size {
+					continue;
+				}
 
-		let p0 = to_check.0.dvec2();
-		let p1 = to_check.1.dvec2();
+				let normal = if dist > f64::EPSILON {
+					diff / dist
+				} else {
+					(segment.bar.1.dvec2() - segment.bar.0.dvec2())
+						.perp()
+						.normalize_or_zero()
+				};
 
-		let dot = (*new_pos - self.pos).dot(angle_vec(&p1, &p0) - angle_vec(&p0, &p1));
-		let target = if dot > 0. { p0 } else { p1 };
-		let dist = self.pos.distance(*new_pos) * (dot.abs() / 8.) * 1.1;
+				self.pos = closest + normal * (self.size + SKIN);
+				pushed = true;
+			}
 
-		self.try_move(&self.pos.move_towards(target, dist), current_map);
+			if !pushed {
+				return;
+			}
+		}
 	}
 }
 
@@ -160,20 +234,20 @@ impl Default for Obj {
 	fn default() -> Self {
 		Self {
 			pos: DVec2::ZERO,
+			prev_pos: DVec2::ZERO,
 			target: DVec2::ZERO,
 			size: 0.,
+			mass: None,
 
 			axis_horizontal: Axis::None,
 			axis_vertical: Axis::None,
 
 			speed: 1.,
+			speed_mult: 1.,
 			stunned: 0.,
 
-			depth: 0,
+			velocity: DVec2::ZERO,
+			friction: 0.8,
 		}
 	}
 }
-
-fn angle_vec(p0: &DVec2, p1: &DVec2) -> DVec2 {
-	DVec2::from_angle(angle_between(p0, p1))
-}
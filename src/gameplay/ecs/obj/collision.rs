@@ -0,0 +1,101 @@
+use macroquad::math::DVec2;
+
+/// Info about where a moving circle hit a segment
+#[derive(Clone, Copy)]
+pub struct Hit {
+	/// How far along the movement the hit occured, in the range of [0; 1]
+	pub time: f64,
+	/// The direction pointing away from the segment, towards the circle
+	pub normal: DVec2,
+}
+
+/// Finds the first point where a circle moving by `motion` touches the segment between `a` and `b`.
+///
+/// Circles that already overlap the segment are ignored, as they should be depenetrated instead.
+pub fn sweep_circle(pos: DVec2, motion: DVec2, radius: f64, a: DVec2, b: DVec2) -> Option<Hit> {
+	let mut best: Option<Hit> = None;
+	let mut try_hit = |hit: Hit| {
+		if best.is_none_or(|best| hit.time < best.time) {
+			best = Some(hit);
+		}
+	};
+
+	// The flat side of the segment
+	let ab = b - a;
+	let len_squared = ab.length_squared();
+	if len_squared > 0. {
+		let mut normal = ab.perp().normalize();
+		if (pos - a).dot(normal) < 0. {
+			normal = -normal;
+		}
+
+		let dist = (pos - a).dot(normal);
+		let speed = motion.dot(normal);
+
+		if speed < 0. && dist >= radius {
+			let time = (dist - radius) / -speed;
+			let contact = pos + motion * time - normal * radius;
+			let along = (contact - a).dot(ab) / len_squared;
+
+			if time <= 1. && (0. ..=1.).contains(&along) {
+				try_hit(Hit { time, normal });
+			}
+		}
+	}
+
+	// The rounded ends of the segment
+	let len_motion = motion.length_squared();
+	if len_motion > 0. {
+		for point in [a, b] {
+			let diff = pos - point;
+			let half_b = diff.dot(motion);
+			let c = diff.length_squared() - radius * radius;
+			let discriminant = half_b * half_b - len_motion * c;
+
+			if c < 0. || half_b >= 0. || discriminant < 0. {
+				continue;
+			}
+
+			let time = (-half_b - discriminant.sqrt()) / len_motion;
+			if (0. ..=1.).contains(&time) {
+				try_hit(Hit {
+					time,
+					normal: (pos + motion * time - point).normalize_or_zero(),
+				});
+			}
+		}
+	}
+
+	best
+}
+
+/// Finds how far along `ray` it crosses the segment between `a` and `b`, in the range of [0; 1].
+///
+/// Rays running parallel to the segment never cross it.
+pub fn intersect_ray(origin: DVec2, ray: DVec2, a: DVec2, b: DVec2) -> Option<f64> {
+	let ab = b - a;
+	let denominator = ray.perp_dot(ab);
+	if denominator.abs() < f64::EPSILON {
+		return None;
+	}
+
+	let time = (a - origin).perp_dot(ab) / denominator;
+	let along = (a - origin).perp_dot(ray) / denominator;
+
+	((0. ..=1.).contains(&time) && (0. ..=1.).contains(&along)).then_some(time)
+}
+
+/// Gets the point on the segment between `a` and `b` that is closest to `pos`
+pub fn closest_point(pos: DVec2, a: DVec2, b: DVec2) -> DVec2 {
+	let ab = b - a;
+	let len_squared = ab.length_squared();
+
+	if len_squared == 0. {
+		return a;
+	}
+
+	a + ab * ((pos - a).dot(ab) / len_squared).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,116 @@
+use macroquad::math::{DVec2, dvec2};
+use raywoke::Barrier;
+
+use crate::{
+	cores::map::grid::{BarrierGrid, Segment, SegmentKind},
+	gameplay::{breakable::Broken, ecs::obj::Obj},
+	utils::tup_vec::Tup64,
+};
+
+use super::sweep_circle;
+
+/// How close positions need to be to be treated as equal, allowing for the gap kept from barriers
+const TOLERANCE: f64 = 0.1;
+
+/// Creates a grid of walls, with each going between the provided points
+fn walls(bars: &[(DVec2, DVec2)]) -> BarrierGrid {
+	BarrierGrid::new(
+		bars.iter()
+			.map(|(a, b)| Segment {
+				bar: Barrier::new(a.tup64(), b.tup64()),
+				kind: SegmentKind::Wall,
+			})
+			.collect(),
+	)
+}
+
+/// A vertical wall at `x = 50`
+fn wall() -> BarrierGrid {
+	walls(&[(dvec2(50., -200.), dvec2(50., 200.))])
+}
+
+/// Moves an Obj at the position by the motion, returning where it ended up and the last normal hit
+fn slide(grid: &BarrierGrid, pos: DVec2, motion: DVec2) -> (DVec2, Option<DVec2>) {
+	let mut obj = Obj::new(pos, pos, 10.);
+	let normal = obj.slide_in(grid, motion, "", &Broken::default());
+
+	(obj.pos, normal)
+}
+
+#[test]
+fn sweep_hits_wall() {
+	let hit = sweep_circle(
+		DVec2::ZERO,
+		dvec2(200., 0.),
+		10.,
+		dvec2(50., -200.),
+		dvec2(50., 200.),
+	)
+	.unwrap();
+
+	assert!((hit.time - 0.2).abs() < f64::EPSILON);
+	assert!(hit.normal.distance(dvec2(-1., 0.)) < f64::EPSILON);
+
+	// Moving away from the wall, or stopping short of it
+	assert!(
+		sweep_circle(
+			DVec2::ZERO,
+			dvec2(-200., 0.),
+			10.,
+			dvec2(50., -200.),
+			dvec2(50., 200.)
+		)
+		.is_none()
+	);
+	assert!(
+		sweep_circle(
+			DVec2::ZERO,
+			dvec2(30., 0.),
+			10.,
+			dvec2(50., -200.),
+			dvec2(50., 200.)
+		)
+		.is_none()
+	);
+}
+
+#[test]
+fn dash_does_not_tunnel() {
+	// Much further than the wall is from the Obj in a single tick
+	let (pos, normal) = slide(&wall(), DVec2::ZERO, dvec2(500., 0.));
+
+	assert!(pos.distance(dvec2(40., 0.)) < TOLERANCE);
+	assert!(pos.x < 40.);
+	assert_eq!(normal, Some(dvec2(-1., 0.)));
+}
+
+#[test]
+fn slides_along_wall() {
+	let (pos, normal) = slide(&wall(), DVec2::ZERO, dvec2(100., 100.));
+
+	// Stopped by the wall horizontally, but keeps all of its vertical movement
+	assert!(pos.distance(dvec2(40., 100.)) < TOLERANCE);
+	assert!(normal.is_some());
+}
+
+#[test]
+fn stops_in_corner() {
+	let grid = walls(&[
+		(dvec2(50., -200.), dvec2(50., 50.)),
+		(dvec2(-200., 50.), dvec2(50., 50.)),
+	]);
+
+	let (pos, _) = slide(&grid, DVec2::ZERO, dvec2(100., 100.));
+
+	assert!(pos.distance(dvec2(40., 40.)) < TOLERANCE);
+	assert!(pos.x < 40. && pos.y < 40.);
+}
+
+#[test]
+fn depenetrates_obj_inside_wall() {
+	let mut obj = Obj::new(dvec2(45., 0.), dvec2(45., 0.), 10.);
+	obj.depenetrate(&wall(), "", &Broken::default());
+
+	assert!(obj.pos.distance(dvec2(40., 0.)) < TOLERANCE);
+	assert!(obj.pos.x < 40.);
+}
@@ -0,0 +1,203 @@
+use std::f64::consts::PI;
+
+use macroquad::{
+	color::Color,
+	math::DVec2,
+	shapes::{draw_circle_lines, draw_line},
+};
+use serde::Deserialize;
+
+use crate::utils::ImmutVec;
+
+use super::{Obj, collision::closest_point};
+
+/// How many straight edges the curve of an arc is split into
+const ARC_SEGMENTS: usize = 8;
+
+/// The shape of an Obj, used when checking if attacks hit.
+///
+/// Every shape is sized by the Obj's size, and those with a direction face the Obj's target.
+#[derive(Clone, Default, Deserialize)]
+pub enum Hitbox {
+	/// A circle, with the size as its radius
+	#[default]
+	Circle,
+	/// A slice of a circle spanning the provided angle in radians, with the size as its radius.
+	/// Angles past a half circle are treated as a half circle.
+	Arc(f64),
+	/// A circle with a hole in its center, where the provided thickness is how far in from the edge it reaches
+	Ring(f64),
+	/// A line from the position to the target, with the size as its radius
+	Capsule,
+	/// A rectangle of the provided length, with the size as half of its width
+	Rect(f64),
+	/// A convex polygon, with its points relative to the position.
+	/// The polygon is rotated so that the positive x axis faces the target.
+	Polygon(ImmutVec<DVec2>),
+}
+
+/// A hitbox placed within the world, as a convex shape with rounded edges
+struct Shape {
+	/// The corners of the shape, with one point being a circle and two being a capsule
+	points: Vec<DVec2>,
+	radius: f64,
+
+	center: DVec2,
+	/// The radius of the hole in the center of the shape, if it has one
+	hole: f64,
+}
+
+impl Hitbox {
+	/// Checks if the hitboxes overlap, when placed at their respective Objs
+	pub fn overlaps(&self, obj: &Obj, other: &Self, other_obj: &Obj) -> bool {
+		let a = self.shape(obj);
+		let b = other.shape(other_obj);
+
+		a.distance(&b) <= a.radius + b.radius && !a.in_hole(&b) && !b.in_hole(&a)
+	}
+
+	/// Draws the outline of the hitbox, for debugging
+	pub fn draw(&self, obj: &Obj, color: Color) {
+		let shape = self.shape(obj);
+		let line = |a: DVec2, b: DVec2| {
+			draw_line(a.x as f32, a.y as f32, b.x as f32, b.y as f32, 1., color);
+		};
+		let circle = |pos: DVec2, radius: f64| {
+			draw_circle_lines(pos.x as f32, pos.y as f32, radius as f32, 1., color);
+		};
+
+		for (a, b) in shape.edges() {
+			line(a, b);
+		}
+		if shape.radius > 0. {
+			for point in &shape.points {
+				circle(*point, shape.radius);
+			}
+		}
+		if shape.hole > 0. {
+			circle(shape.center, shape.hole);
+		}
+	}
+
+	fn shape(&self, obj: &Obj) -> Shape {
+		let pos = obj.pos;
+		let dir = (obj.target - pos).try_normalize().unwrap_or(DVec2::X);
+		let (points, radius, hole) = match self {
+			Hitbox::Circle => (vec![pos], obj.size, 0.),
+			Hitbox::Arc(angle) => {
+				let angle = angle.clamp(0., PI);
+				let arc = (0..=ARC_SEGMENTS).map(|i| {
+					let offset = angle * (i as f64 / ARC_SEGMENTS as f64 - 0.5);
+					pos + DVec2::from_angle(offset).rotate(dir) * obj.size
+				});
+
+				(std::iter::once(pos).chain(arc).collect(), 0., 0.)
+			}
+			Hitbox::Ring(thickness) => (vec![pos], obj.size, (obj.size - thickness).max(0.)),
+			Hitbox::Capsule => (vec![pos, obj.target], obj.size, 0.),
+			Hitbox::Rect(length) => {
+				let along = dir * *length / 2.;
+				let across = dir.perp() * obj.size;
+
+				(
+					vec![
+						pos - along - across,
+						pos + along - across,
+						pos + along + across,
+						pos - along + across,
+					],
+					0.,
+					0.,
+				)
+			}
+			Hitbox::Polygon(points) => (
+				points.iter().map(|point| pos + point.rotate(dir)).collect(),
+				0.,
+				0.,
+			),
+		};
+
+		// Empty polygons are treated as a single point
+		let points = if points.is_empty() { vec![pos] } else { points };
+
+		Shape {
+			points,
+			radius,
+			center: pos,
+			hole,
+		}
+	}
+}
+
+impl Shape {
+	/// Gets the edges of the shape, with circles having a single edge of no length
+	fn edges(&self) -> impl Iterator<Item = (DVec2, DVec2)> {
+		let len = self.points.len();
+		let count = if len <= 2 { len - 1 } else { len };
+
+		(0..count.max(1)).map(move |i| (self.points[i], self.points[(i + 1) % len]))
+	}
+
+	/// Gets the distance between the shapes, ignoring their radii
+	fn distance(&self, other: &Shape) -> f64 {
+		if self.contains(other.points[0]) || other.contains(self.points[0]) {
+			return 0.;
+		}
+
+		self.edges()
+			.flat_map(|a| other.edges().map(move |b| segment_distance(a, b)))
+			.fold(f64::INFINITY, f64::min)
+	}
+
+	/// Checks if the point is inside the shape, ignoring its radius
+	fn contains(&self, point: DVec2) -> bool {
+		if self.points.len() < 3 {
+			return false;
+		}
+
+		// The point has to be on the same side of every edge
+		let mut side = 0.;
+		for (a, b) in self.edges() {
+			let cross = (b - a).perp_dot(point - a);
+			if cross * side < 0. {
+				return false;
+			}
+			if cross != 0. {
+				side = cross;
+			}
+		}
+
+		true
+	}
+
+	/// Checks if the other shape is entirely within this shape's hole
+	fn in_hole(&self, other: &Shape) -> bool {
+		self.hole > 0.
+			&& other
+				.points
+				.iter()
+				.all(|point| point.distance(self.center) + other.radius < self.hole)
+	}
+}
+
+/// Gets the distance between two segments
+fn segment_distance((a1, a2): (DVec2, DVec2), (b1, b2): (DVec2, DVec2)) -> f64 {
+	if segments_cross(a1, a2, b1, b2) {
+		return 0.;
+	}
+
+	[
+		a1.distance(closest_point(a1, b1, b2)),
+		a2.distance(closest_point(a2, b1, b2)),
+		b1.distance(closest_point(b1, a1, a2)),
+		b2.distance(closest_point(b2, a1, a2)),
+	]
+	.into_iter()
+	.fold(f64::INFINITY, f64::min)
+}
+
+fn segments_cross(a1: DVec2, a2: DVec2, b1: DVec2, b2: DVec2) -> bool {
+	let side = |a: DVec2, b: DVec2, point: DVec2| (b - a).perp_dot(point - a);
+
+	side(a1, a2, b1) * side(a1, a2, b2) < 0. && side(b1, b2, a1) * side(b1, b2, a2) < 0.
+}
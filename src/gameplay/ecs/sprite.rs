@@ -3,10 +3,9 @@ use rustc_hash::FxHashMap;
 use crate::{
 	gameplay::draw::process::{scale, to_texture},
 	utils::{
-		angle_between,
+		TICK, angle_between,
 		error::{EtherVoidError, EvoidResult},
 		resources::{config::access_config, textures::access_image},
-		smart_time,
 	},
 };
 
@@ -32,6 +31,12 @@ pub struct Sprite {
 	frames: Frames,
 
 	shaking: f64,
+	/// How much longer the sprite is drawn white for
+	flashing: f64,
+	/// The sprite's image made entirely white, which is created the first time it flashes
+	flash_cache: Option<Texture2D>,
+	/// The color the sprite is multiplied by when rendered
+	pub tint: Color,
 
 	current_anim: Option<String>,
 	anims: FxHashMap<String, Frames>,
@@ -78,6 +83,9 @@ impl Sprite {
 			frames,
 
 			shaking: 0.,
+			flashing: 0.,
+			flash_cache: None,
+			tint: WHITE,
 
 			current_anim: None,
 			anims,
@@ -86,7 +94,10 @@ impl Sprite {
 
 	pub fn update(&mut self, new_obj: Obj) {
 		if self.shaking > 0. {
-			self.shaking -= smart_time();
+			self.shaking -= TICK;
+		}
+		if self.flashing > 0. {
+			self.flashing -= TICK;
 		}
 
 		if let Some(ref anim) = self.current_anim {
@@ -102,6 +113,8 @@ impl Sprite {
 
 		if self.obj.pos == new_obj.pos && self.rotation == Rotation::EightWay {
 			self.frames.reset();
+			// Otherwise, the sprite would keep being drawn between its last two positions
+			self.obj.prev_pos = new_obj.prev_pos;
 		} else {
 			self.frames.update();
 			self.obj = new_obj;
@@ -116,8 +129,21 @@ impl Sprite {
 		self.shaking = 20.;
 	}
 
+	/// Makes the sprite briefly drawn white
+	pub fn flash(&mut self) {
+		self.flashing = 6.;
+	}
+
+	/// Checks if the current animation has played through at least once
 	pub fn anim_completed(&self) -> bool {
-		self.frames.anim_completed
+		match self
+			.current_anim
+			.as_ref()
+			.and_then(|anim| self.anims.get(anim))
+		{
+			Some(anim) => anim.anim_completed,
+			None => self.frames.anim_completed,
+		}
 	}
 
 	pub fn get_current_anim(&mut self) -> Option<&str> {
@@ -142,6 +168,7 @@ impl Sprite {
 	pub fn set_img(&mut self, img: DynamicImage) {
 		self.img = img;
 		self.cache = None;
+		self.flash_cache = None;
 	}
 
 	pub fn img(&self) -> &DynamicImage {
@@ -161,8 +188,14 @@ impl Sprite {
 		self.current_anim = None;
 	}
 
-	pub fn as_render_params(&mut self) -> (Texture2D, DVec2, Option<DrawTextureParams>) {
+	/// Gets what is needed to render the sprite.
+	/// The sprite is positioned between its previous and current positions based upon `alpha`.
+	pub fn as_render_params(
+		&mut self,
+		alpha: f64,
+	) -> (Texture2D, DVec2, Option<DrawTextureParams>) {
 		let screen_scale = access_config().screen_scale;
+		let render_pos = self.obj.interpolated(alpha).pos;
 
 		let size = if self.rotation == Rotation::EightWay {
 			self.img.height() / 5
@@ -209,17 +242,19 @@ impl Sprite {
 		(
 			if self.rotation == Rotation::Angle {
 				self.texture_angle(x_pos, y_pos)
+			} else if self.flashing > 0. {
+				self.texture_flash()
 			} else {
 				self.texture_non_angle()
 			},
 			dvec2(
-				self.obj.pos.x
+				render_pos.x
 					+ match self.rotation {
 						Rotation::Angle => 0.,
 						Rotation::Static => self.img.width() as f64 / 2.,
 						Rotation::EightWay => self.img.width() as f64,
 					},
-				self.obj.pos.y
+				render_pos.y
 					+ match self.rotation {
 						Rotation::Angle => 0.,
 						Rotation::Static => self.img.width() as f64 / 2.,
@@ -271,6 +306,19 @@ impl Sprite {
 		img
 	}
 
+	fn texture_flash(&mut self) -> Texture2D {
+		self.flash_cache
+			.get_or_insert_with(|| {
+				let mut img = self.img.to_rgba8();
+				for pixel in img.pixels_mut() {
+					pixel.0 = [255, 255, 255, pixel.0[3]];
+				}
+
+				to_texture(&DynamicImage::ImageRgba8(img))
+			})
+			.clone()
+	}
+
 	fn texture_non_angle(&mut self) -> Texture2D {
 		if let Some((_, texture)) = &self.cache {
 			return texture.clone();
@@ -314,7 +362,7 @@ impl Frames {
 	}
 
 	fn update(&mut self) {
-		self.anim_time += smart_time();
+		self.anim_time += TICK;
 
 		if self.anim_time as usize >= (self.frame_order.len() * self.frame_time as usize) {
 			self.anim_time = 0.;
@@ -324,6 +372,7 @@ impl Frames {
 
 	fn reset(&mut self) {
 		self.anim_time = 0.;
+		self.anim_completed = false;
 	}
 
 	fn get_frame(&self) -> u32 {
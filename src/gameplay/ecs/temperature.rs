@@ -0,0 +1,57 @@
+use crate::{
+	cores::temperaturetype::TemperatureType,
+	utils::{TICK, resources::temperatures::access_temperature},
+};
+
+use super::{effects::Effects, health::Health};
+
+/// How hot an entity is, which boosts its damage until it overheats
+pub struct Temperature {
+	pub current: f64,
+	/// The key of the [`TemperatureType`] containing the stats used
+	key: String,
+}
+
+impl Temperature {
+	pub fn new(key: &str) -> Self {
+		Self {
+			current: 0.,
+			key: key.to_owned(),
+		}
+	}
+
+	pub fn stats(&self) -> Option<&'static TemperatureType> {
+		access_temperature(&self.key)
+	}
+
+	/// Raises the temperature by the provided amount
+	pub fn heat(&mut self, amount: f64) {
+		self.current += amount;
+	}
+
+	/// Gets what damage dealt is multiplied by at the current temperature
+	pub fn damage_multiplier(&self) -> f64 {
+		match self.stats() {
+			Some(stats) if self.current >= stats.hot => stats.hot_damage,
+			Some(stats) if self.current >= stats.warm => stats.warm_damage,
+			_ => 1.,
+		}
+	}
+
+	/// Heats up from damage taken, cools down over time, and overheats if too hot
+	pub fn update(&mut self, health: &mut Health, effects: &mut Effects) {
+		self.current += health.heat_taken;
+		health.heat_taken = 0.;
+
+		let Some(stats) = self.stats() else {
+			return;
+		};
+
+		if self.current >= stats.max {
+			effects.apply(&stats.overheat_effect);
+			self.current = stats.overheat_reset;
+		}
+
+		self.current = (self.current - stats.cooling * TICK).max(0.);
+	}
+}
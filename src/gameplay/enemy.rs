@@ -1,28 +1,61 @@
-use crate::cores::enemytype::EnemyType;
-use macroquad::math::DVec2;
+use crate::{
+	cores::enemytype::{EnemyDrop, EnemyType},
+	utils::{
+		ImmutVec,
+		resources::{audio::play_random_sound, items::access_item},
+	},
+};
+use macroquad::{color::WHITE, math::DVec2, rand::gen_range};
 use stecs::prelude::*;
 
-use super::ecs::{
-	behavior::goal::Goals,
-	health::Health,
-	obj::Obj,
-	sprite::{Frames, Rotation, Sprite},
+use super::{
+	ecs::{
+		behavior::goal::Goals,
+		effects::Effects,
+		health::Health,
+		obj::{Obj, hitbox::Hitbox},
+		sprite::{Frames, Rotation, Sprite},
+	},
+	pickup::Pickup,
 };
 
+/// How far from an enemy its drops can land
+const DROP_SPREAD: f64 = 12.;
+/// The animation played when an enemy dies
+const DEATH_ANIM: &str = "death";
+
 #[derive(SplitFields)]
 pub struct Enemy {
 	health: Health,
 	obj: Obj,
 	goals: Goals,
 	pub sprite: Sprite,
+	effects: Effects,
+	hitbox: Hitbox,
+
+	drops: ImmutVec<EnemyDrop>,
+	death_sounds: ImmutVec<String>,
+	/// If true, the enemy was spawned by an arena, and has to be killed for the arena to continue
+	pub in_arena: bool,
+}
+
+/// What remains of an enemy while its death animation plays
+#[derive(SplitFields)]
+pub struct Corpse {
+	obj: Obj,
+	pub sprite: Sprite,
 }
 
 impl Enemy {
 	pub fn from_type(enemytype: &EnemyType, pos: &DVec2) -> Self {
-		let obj = Obj::new(*pos, *pos, enemytype.size);
+		let mut obj = Obj::new(*pos, *pos, enemytype.size);
+		obj.mass = enemytype.mass;
+
+		let mut health = Health::new(enemytype.max_health);
+		health.multipliers.clone_from(&enemytype.resistances);
 
 		Self {
-			health: Health::new(enemytype.max_health),
+			health,
 			obj,
 			goals: Goals::from_scripts(&enemytype.goals),
 			sprite: Sprite::new(
@@ -32,6 +65,46 @@ impl Enemy {
 				Frames::new_entity(),
 				enemytype.anims.clone(),
 			),
+			effects: Effects::default(),
+			hitbox: enemytype.hitbox.clone(),
+
+			drops: enemytype.drops.clone(),
+			death_sounds: enemytype.death_sounds.clone(),
+			in_arena: false,
 		}
 	}
+
+	/// Starts the enemy's death, by running the `on_death` function of its goals,
+	/// playing its death sound and dropping its items.
+	///
+	/// Returns its corpse if it has a death animation to play.
+	pub fn die(mut self, obj_player: &Obj, pickups: &mut StructOf<Vec<Pickup>>) -> Option<Corpse> {
+		self.goals.on_death(&self.obj, obj_player);
+
+		if !self.death_sounds.is_empty() {
+			play_random_sound(&self.death_sounds);
+		}
+
+		for drop in &self.drops {
+			if gen_range(0., 1.) >= drop.chance {
+				continue;
+			}
+			let Some(item) = access_item(&drop.item) else {
+				continue;
+			};
+
+			let offset = DVec2::from_angle(gen_range(0., std::f64::consts::TAU))
+				* gen_range(0., DROP_SPREAD);
+			pickups.insert(Pickup::dropped(item, &(self.obj.pos + offset)));
+		}
+
+		self.sprite.set_new_anim(DEATH_ANIM.to_owned()).ok()?;
+		// Status effects no longer apply once dead
+		self.sprite.tint = WHITE;
+
+		Some(Corpse {
+			obj: self.obj,
+			sprite: self.sprite,
+		})
+	}
 }
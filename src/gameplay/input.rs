@@ -0,0 +1,239 @@
+use std::path::PathBuf;
+
+use bincode::{Decode, Encode};
+use macroquad::math::{DVec2, dvec2};
+
+use crate::{
+	data::{
+		config::keymap::{Key, KeyMap},
+		replay::Replay,
+	},
+	utils::{
+		mouse_pos, mouse_pos_local,
+		resources::{Global, config::access_config, global},
+	},
+};
+
+/// Stores the input for the current tick
+static INPUT: Global<Input> = global!(Input::default());
+
+/// The actions that the player can perform during gameplay
+#[derive(Clone, Copy)]
+pub enum Action {
+	Up,
+	Down,
+	Left,
+	Right,
+	Dash,
+
+	Sword,
+	Gun,
+	ChangeSword,
+	ChangeGun,
+}
+
+/// The input for a single simulation tick
+#[derive(Clone, Copy, Default, Encode, Decode)]
+pub struct Input {
+	down: u16,
+	pressed: u16,
+	mouse_pos: (f64, f64),
+	mouse_pos_local: (f64, f64),
+	/// Whether gameplay was paused since the previous tick
+	paused: bool,
+}
+
+/// Provides the input for each tick, either from the player or from a replay
+pub struct InputReader {
+	source: InputSource,
+	/// Actions that have been pressed since the previous tick
+	pressed: u16,
+	/// Whether gameplay has been paused since the previous tick
+	paused: bool,
+}
+
+enum InputSource {
+	Live,
+	/// Live input that is stored, and written to the provided file once gameplay ends
+	Record(Box<Replay>, PathBuf),
+	Replay(std::vec::IntoIter<Input>),
+}
+
+impl Action {
+	const ALL: [Self; 9] = [
+		Self::Up,
+		Self::Down,
+		Self::Left,
+		Self::Right,
+		Self::Dash,
+		Self::Sword,
+		Self::Gun,
+		Self::ChangeSword,
+		Self::ChangeGun,
+	];
+
+	fn bit(self) -> u16 {
+		1 << self as u16
+	}
+
+	fn key(self, keymap: &KeyMap) -> &Key {
+		match self {
+			Self::Up => &keymap.up,
+			Self::Down => &keymap.down,
+			Self::Left => &keymap.left,
+			Self::Right => &keymap.right,
+			Self::Dash => &keymap.dash,
+			Self::Sword => &keymap.sword,
+			Self::Gun => &keymap.gun,
+			Self::ChangeSword => &keymap.change_sword,
+			Self::ChangeGun => &keymap.change_gun,
+		}
+	}
+}
+
+impl Input {
+	/// Checks if the action is held down
+	pub fn is_down(&self, action: Action) -> bool {
+		self.down & action.bit() != 0
+	}
+
+	/// Checks if the action was pressed since the previous tick
+	pub fn is_pressed(&self, action: Action) -> bool {
+		self.pressed & action.bit() != 0
+	}
+
+	/// Gets the position of the mouse, relative to the center of the screen
+	pub fn mouse_pos(&self) -> DVec2 {
+		dvec2(self.mouse_pos.0, self.mouse_pos.1)
+	}
+
+	/// Gets the position of the mouse in the range of [1; -1]
+	pub fn mouse_pos_local(&self) -> DVec2 {
+		dvec2(self.mouse_pos_local.0, self.mouse_pos_local.1)
+	}
+
+	/// Checks if gameplay was paused since the previous tick
+	pub fn was_paused(&self) -> bool {
+		self.paused
+	}
+}
+
+#[cfg(test)]
+impl Input {
+	/// Creates the input for a tick where the actions are held down or were just pressed,
+	/// which is used to script sessions in tests
+	pub fn scripted(down: &[Action], pressed: &[Action], mouse_pos: DVec2) -> Self {
+		let bits = |actions: &[Action]| actions.iter().fold(0, |bits, action| bits | action.bit());
+
+		Self {
+			down: bits(down) | bits(pressed),
+			pressed: bits(pressed),
+			mouse_pos: (mouse_pos.x, mouse_pos.y),
+			..Default::default()
+		}
+	}
+}
+
+impl InputReader {
+	/// Reads input from the player
+	pub fn live() -> Self {
+		Self::new(InputSource::Live)
+	}
+
+	/// Reads input from the player, storing it within the replay
+	pub fn record(replay: Replay, dir: PathBuf) -> Self {
+		Self::new(InputSource::Record(Box::new(replay), dir))
+	}
+
+	/// Reads input from the inputs stored within a replay
+	pub fn replay(inputs: Vec<Input>) -> Self {
+		Self::new(InputSource::Replay(inputs.into_iter()))
+	}
+
+	fn new(source: InputSource) -> Self {
+		Self {
+			source,
+			pressed: 0,
+			paused: false,
+		}
+	}
+
+	pub fn is_replay(&self) -> bool {
+		matches!(self.source, InputSource::Replay(_))
+	}
+
+	/// Stores the actions pressed during the current frame, so that they are not lost
+	/// if no tick happens before the next frame
+	pub fn poll(&mut self) {
+		if self.is_replay() {
+			return;
+		}
+
+		let keymap = &access_config().keymap;
+		for action in Action::ALL {
+			if action.key(keymap).is_pressed() {
+				self.pressed |= action.bit();
+			}
+		}
+	}
+
+	/// Marks gameplay as paused, which is stored within the input for the next tick
+	pub fn pause(&mut self) {
+		self.paused = true;
+	}
+
+	/// Moves onto the input for the next tick.
+	/// Returns `false` if there is no input left, which only happens once a replay has finished.
+	pub fn advance(&mut self) -> bool {
+		let input = match &mut self.source {
+			InputSource::Replay(inputs) => match inputs.next() {
+				Some(input) => input,
+				None => return false,
+			},
+			InputSource::Live | InputSource::Record(..) => {
+				let input = self.read_live();
+				if let InputSource::Record(replay, _) = &mut self.source {
+					replay.inputs.push(input);
+				}
+				input
+			}
+		};
+
+		*INPUT.write() = input;
+		true
+	}
+
+	fn read_live(&mut self) -> Input {
+		let keymap = &access_config().keymap;
+
+		let mut down = 0;
+		for action in Action::ALL {
+			if action.key(keymap).is_down() {
+				down |= action.bit();
+			}
+		}
+
+		let mouse_pos = mouse_pos();
+		let mouse_pos_local = mouse_pos_local();
+
+		Input {
+			down,
+			pressed: std::mem::take(&mut self.pressed),
+			mouse_pos: (mouse_pos.x, mouse_pos.y),
+			mouse_pos_local: (mouse_pos_local.x, mouse_pos_local.y),
+			paused: std::mem::take(&mut self.paused),
+		}
+	}
+
+	/// Writes the recording to its file, if input is being recorded
+	pub fn finish(&self) {
+		if let InputSource::Record(replay, dir) = &self.source {
+			replay.save(dir);
+		}
+	}
+}
+
+/// Gets the input for the current tick
+pub fn input() -> Input {
+	*INPUT.read()
+}
@@ -24,7 +24,8 @@ pub struct Npc {
 
 impl Npc {
 	pub fn from_type(npctype: &NpcType, pos: &DVec2) -> Self {
-		let obj = Obj::new(*pos, *pos, 15.);
+		let mut obj = Obj::new(*pos, *pos, 15.);
+		obj.mass = npctype.mass;
 
 		Self {
 			obj,
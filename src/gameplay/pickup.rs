@@ -0,0 +1,87 @@
+use macroquad::math::DVec2;
+use rustc_hash::FxHashMap;
+use stecs::prelude::*;
+
+use crate::cores::itemtype::{ItemEffect, ItemType};
+
+use super::{
+	Gameplay,
+	ecs::{
+		obj::Obj,
+		remove_where,
+		sprite::{Frames, Rotation, Sprite},
+	},
+};
+
+#[derive(SplitFields)]
+pub struct Pickup {
+	obj: Obj,
+	sprite: Sprite,
+
+	effect: ItemEffect,
+	/// The key used to store the pickup within the save, if it should stay collected
+	save_key: Option<String>,
+}
+
+impl Pickup {
+	pub fn from_type(itemtype: &ItemType, pos: &DVec2, save_key: String) -> Self {
+		let obj = Obj::new(*pos, *pos, itemtype.radius);
+
+		Self {
+			obj,
+			sprite: Sprite::new(
+				obj,
+				&itemtype.sprite,
+				Rotation::Static,
+				Frames::new_static(),
+				FxHashMap::default(),
+			),
+
+			effect: itemtype.effect.clone(),
+			save_key: itemtype.once.then_some(save_key),
+		}
+	}
+
+	/// Creates a pickup that was dropped, which is never saved as collected
+	pub fn dropped(itemtype: &ItemType, pos: &DVec2) -> Self {
+		Self {
+			save_key: None,
+			..Self::from_type(itemtype, pos, String::new())
+		}
+	}
+}
+
+/// Collects any pickups that the player is touching
+pub fn collect_pickups(gameplay: &mut Gameplay) {
+	let Some(obj_player) = gameplay.world.player.obj.first().copied() else {
+		return;
+	};
+	let collected = remove_where(&mut gameplay.world.pickups, |pickups, id| {
+		obj_player.is_touching(&pickups.obj[id])
+	});
+	let Some(player) = gameplay.world.player.get_mut(0) else {
+		return;
+	};
+
+	for pickup in collected {
+		match pickup.effect {
+			ItemEffect::Heal(amount) => {
+				player.health.hp = (player.health.hp + amount).min(player.health.max);
+			}
+			ItemEffect::Currency(amount) => gameplay.save.money += amount,
+			ItemEffect::Weapon(weapon) => {
+				player.inventory.unlock(&weapon);
+				gameplay.save.unlocked_weapons.insert(weapon);
+			}
+			ItemEffect::MaxHealth(amount) => {
+				player.health.max += amount;
+				player.health.hp += amount;
+				gameplay.save.max_health += amount;
+			}
+		}
+
+		if let Some(key) = pickup.save_key {
+			gameplay.save.collected_pickups.insert(key);
+		}
+	}
+}
@@ -1,69 +1,105 @@
+use mlua::Function;
 use rustc_hash::FxHashMap;
 use stecs::prelude::*;
 
 use super::{
-	combat::{Attack, Owner},
+	combat::{Attack, AttackType, Owner, script::AttackScript},
 	ecs::{
 		behavior::player::PlayerController,
+		effects::Effects,
 		health::Health,
-		obj::Obj,
+		obj::{Obj, hitbox::Hitbox},
 		sprite::{Frames, Rotation, Sprite},
+		temperature::Temperature,
 	},
+	input::input,
 };
 
 use crate::{
+	cores::{
+		script::Script,
+		weapontype::{WeaponSlot, WeaponType},
+	},
+	data::save::Save,
 	gameplay::draw::ui::PlayerUi,
-	utils::{mouse_pos, mouse_pos_local, resources::audio::play_random_sound},
+	utils::{
+		TICK,
+		error::EvoidResult,
+		lua::LuaDVec2,
+		resources::{
+			audio::play_random_sound,
+			weapons::{access_weapon, access_weapons},
+		},
+	},
 };
 
 use macroquad::prelude::*;
 
+/// The key of the player's [`TemperatureType`](crate::cores::temperaturetype::TemperatureType)
+const TEMPERATURE: &str = "default:player";
+/// The most gun energy the player can have
+pub const MAX_ENERGY: f64 = 100.;
+
 #[derive(SplitFields)]
 pub struct Player {
 	pub health: Health,
 	pub obj: Obj,
 	pub controller: PlayerController,
 	pub sprite: Sprite,
+	pub effects: Effects,
+	pub temperature: Temperature,
+	pub hitbox: Hitbox,
 
 	pub ui: PlayerUi,
 	pub inventory: Inventory,
 }
 
 pub struct Inventory {
-	pub swords: [WeaponInfo; 3],
-	pub guns: [WeaponInfo; 3],
+	pub swords: Vec<WeaponInfo>,
+	pub guns: Vec<WeaponInfo>,
 	pub current_sword: usize,
 	pub current_gun: usize,
+	/// Used up by guns, and gained by hitting enemies with swords and parrying
+	pub energy: f64,
 }
 
-/// Contains info about one of the player's weaponsa
+/// Contains info about one of the player's weapons
 pub struct WeaponInfo {
-	pub weapon: Weapon,
+	/// The key of the weapon's [`WeaponType`]
+	pub weapon: String,
 	pub unlocked: bool,
 	pub cooldown: f64,
+	/// The index of the next attack in the weapon's combo
+	pub combo: usize,
+	/// How much longer the combo can be continued, after the cooldown has ended
+	pub combo_window: f64,
 }
 
-pub enum Weapon {
-	// Swords
-	Sword,
-	Hammer,
-	Boomerang,
-
-	// Gunssword_
-	Pistol,
-	Shotgun,
-	RadioCannon,
+impl WeaponInfo {
+	/// Counts down the cooldown, and then the combo window once the weapon is ready
+	pub fn update(&mut self) {
+		if self.cooldown >= 0. {
+			self.cooldown -= TICK;
+		} else if self.combo_window > 0. {
+			self.combo_window -= TICK;
+		}
+	}
 }
 
 impl Player {
-	pub fn new() -> Self {
+	pub fn new(save: &Save) -> Self {
 		let pos = DVec2::new(0., 0.);
-		let obj = Obj::new(pos, pos, 15.);
+		let mut obj = Obj::new(pos, pos, 15.);
+		obj.mass = Some(10.);
+		obj.friction = 0.9;
 
-		Self {
-			health: Health::new(100.),
+		let mut player = Self {
+			health: Health::new(100. + save.max_health),
 			obj,
 			controller: PlayerController::default(),
+			effects: Effects::default(),
+			temperature: Temperature::new(TEMPERATURE),
+			hitbox: Hitbox::Circle,
 			sprite: Sprite::new(
 				obj,
 				"default:entity/player/player_spritesheet_wip",
@@ -80,134 +116,171 @@ impl Player {
 				"default:ui/temp_bar_second",
 				"default:ui/temp_bar_third",
 			),
-			inventory: Inventory {
-				swords: [
-					WeaponInfo {
-						weapon: Weapon::Sword,
-						unlocked: true,
-						cooldown: 0.,
-					},
-					WeaponInfo {
-						weapon: Weapon::Hammer,
-						unlocked: true,
-						cooldown: 0.,
-					},
-					WeaponInfo {
-						weapon: Weapon::Boomerang,
-						unlocked: true,
-						cooldown: 0.,
-					},
-				],
-				guns: [
-					WeaponInfo {
-						weapon: Weapon::Pistol,
-						unlocked: true,
-						cooldown: 0.,
-					},
-					WeaponInfo {
-						weapon: Weapon::Shotgun,
-						unlocked: true,
-						cooldown: 0.,
-					},
-					WeaponInfo {
-						weapon: Weapon::RadioCannon,
-						unlocked: true,
-						cooldown: 0.,
-					},
-				],
-				current_sword: 0,
-				current_gun: 0,
-			},
+			inventory: Inventory::new(),
+		};
+
+		for weapon in &save.unlocked_weapons {
+			player.inventory.unlock(weapon);
 		}
+
+		player
 	}
 }
 
 impl Inventory {
-	// Gets a sword attack based upon the currently selected sword
-	pub fn attack_sword(&mut self, pos: DVec2) -> Attack {
-		match self.swords[self.current_sword].weapon {
-			Weapon::Sword => {
-				play_random_sound(&[
-					"default:sfx/sword_1",
-					"default:sfx/sword_2",
-					"default:sfx/sword_3",
-				]);
-
-				self.swords[self.current_sword].cooldown = 16.;
-				Attack::new_physical(
-					Obj::new(pos, pos + mouse_pos_local(), 36.),
-					10.,
-					Owner::Player,
-					"default:attacks/slash",
-				)
-			}
-			Weapon::Hammer => {
-				self.swords[self.current_sword].cooldown = 32.;
-				Attack::new_burst(
-					Obj::new(pos, pos, 36.),
-					10.,
-					Owner::Player,
-					"default:attacks/burst",
-				)
-			}
-			Weapon::Boomerang => {
-				self.swords[self.current_sword].cooldown = 48.;
-				Attack::new_projectile(
-					Obj::new(pos, mouse_pos() * 999., 10.),
-					10.,
-					Owner::Player,
-					"default:attacks/projectile-player",
-				)
-			}
+	/// Creates an inventory containing every weapon, sorted by their order
+	pub fn new() -> Self {
+		let weapons = access_weapons();
+		let mut sorted: Vec<(&String, &WeaponType)> = weapons.iter().collect();
+		sorted.sort_by_key(|(key, weapon)| (weapon.order, *key));
+
+		let slot = |slot: WeaponSlot| -> Vec<WeaponInfo> {
+			sorted
+				.iter()
+				.filter(|(_, weapon)| weapon.slot == slot)
+				.map(|(key, weapon)| WeaponInfo {
+					weapon: (*key).clone(),
+					unlocked: !weapon.locked,
+					cooldown: 0.,
+					combo: 0,
+					combo_window: 0.,
+				})
+				.collect()
+		};
 
-			_ => unreachable!("Gun in Sword position"),
+		let swords = slot(WeaponSlot::Sword);
+		let guns = slot(WeaponSlot::Gun);
+
+		Self {
+			current_sword: swords.iter().position(|info| info.unlocked).unwrap_or(0),
+			current_gun: guns.iter().position(|info| info.unlocked).unwrap_or(0),
+			swords,
+			guns,
+			energy: MAX_ENERGY,
 		}
 	}
 
-	// Gets a gun attack based upon the currently selected gun
-	pub fn attack_gun(&mut self, pos: DVec2) -> Attack {
-		match self.guns[self.current_gun].weapon {
-			Weapon::Pistol => {
-				self.guns[self.current_gun].cooldown = 16.;
-				Attack::new_projectile(
-					Obj::new(pos, mouse_pos() * 999., 6.),
-					10.,
-					Owner::Player,
-					"default:attacks/projectile-player",
-				)
-			}
-			Weapon::Shotgun => Attack::new_burst(
-				Obj::new(pos, pos, 16.),
-				10.,
-				Owner::Player,
-				"default:attacks/burst",
-			),
-			Weapon::RadioCannon => {
-				self.guns[self.current_gun].cooldown = 48.;
-				Attack::new_hitscan(
-					Obj::new(pos, mouse_pos() * 999., 6.),
-					6.,
-					Owner::Player,
-					"default:attacks/hitscan-player",
-				)
-			}
+	/// Adds gun energy, up to the maximum
+	pub fn gain_energy(&mut self, energy: f64) {
+		self.energy = (self.energy + energy).min(MAX_ENERGY);
+	}
 
-			_ => unreachable!("Sword in Gun position"),
+	/// Unlocks the provided weapon
+	pub fn unlock(&mut self, weapon: &str) {
+		for info in self.swords.iter_mut().chain(self.guns.iter_mut()) {
+			if info.weapon == weapon {
+				info.unlocked = true;
+			}
 		}
 	}
-}
 
-pub fn swap_weapons(current_weapon: usize, weapons: &[WeaponInfo]) -> usize {
-	let mut to_return: usize = current_weapon;
+	/// Gets the attacks from the currently selected weapon in the slot, if it is ready to be used,
+	/// alongside how long each one should wait before being spawned.
+	/// The attacks' damage is boosted by the temperature, which the weapon then heats up.
+	pub fn attack(
+		&mut self,
+		slot: WeaponSlot,
+		pos: DVec2,
+		temperature: &mut Temperature,
+	) -> Option<Vec<(f64, Attack)>> {
+		let info = match slot {
+			WeaponSlot::Sword => self.swords.get_mut(self.current_sword),
+			WeaponSlot::Gun => self.guns.get_mut(self.current_gun),
+		}?;
+		if !info.unlocked || info.cooldown > 0. {
+			return None;
+		}
+
+		let weapon = access_weapon(&info.weapon)?;
+		if self.energy < weapon.energy_cost {
+			return None;
+		}
+		self.energy -= weapon.energy_cost;
+
+		// Continuing the combo if the weapon was used recently enough, and starting it over otherwise
+		if info.combo_window <= 0. || info.combo >= weapon.combo.len() {
+			info.combo = 0;
+		}
+		let step = weapon.combo.get(info.combo);
+		info.combo += 1;
+		info.combo_window = step.map_or(0., |step| step.window);
+		info.cooldown = step
+			.and_then(|step| step.cooldown)
+			.unwrap_or(weapon.cooldown);
 
-	loop {
-		to_return += 1;
-		if current_weapon >= weapons.len() - 1 {
-			to_return = 0;
+		let damage = step.and_then(|step| step.damage).unwrap_or(weapon.damage);
+		let damage = damage * temperature.damage_multiplier();
+		temperature.heat(weapon.heat);
+
+		let target = match weapon.attack {
+			AttackType::Physical => pos + input().mouse_pos_local(),
+			AttackType::Burst => pos,
+			AttackType::Projectile | AttackType::Hitscan => input().mouse_pos() * 999.,
+		};
+
+		if !weapon.sounds.is_empty() {
+			play_random_sound(&weapon.sounds);
+		}
+
+		if let Some(key) = &weapon.script
+			&& let Err(e) = on_attack(key, pos, target)
+		{
+			error!("Weapon {} failed to run {key}: {e}", info.weapon);
 		}
 
-		if weapons[to_return].unlocked {
-			return to_return;
+		let mut atk = Attack::new(
+			&weapon.attack,
+			Obj::new(
+				pos,
+				target,
+				step.and_then(|step| step.size).unwrap_or(weapon.size),
+			),
+			damage,
+			Owner::Player,
+			step.and_then(|step| step.sprite.as_ref())
+				.unwrap_or(&weapon.sprite),
+		);
+		if let Some(damage_type) = &weapon.damage_type {
+			atk.damage_type.clone_from(damage_type);
+		}
+		atk.effects = weapon.effects.to_vec();
+		if let Some(step) = step {
+			atk.effects.extend(step.effects.iter().cloned());
 		}
+		if let Some(parry) = &weapon.parry {
+			atk.parry = Some(parry.clone());
+		}
+		atk.projectile = weapon.projectile.clone();
+		atk.energy = weapon.energy_gain;
+		if let Some(hitbox) = &weapon.hitbox {
+			atk.hitbox = hitbox.clone();
+		}
+		if let Some(key) = &weapon.attack_script {
+			match AttackScript::new(key) {
+				Ok(script) => atk.script = Some(script),
+				Err(e) => error!("Weapon {} failed to load {key}: {e}", info.weapon),
+			}
+		}
+
+		Some(match &weapon.pattern {
+			Some(pattern) => pattern.emit(&atk),
+			None => vec![(0., atk)],
+		})
 	}
 }
+
+/// Calls the `on_attack` function of a weapon's script
+fn on_attack(key: &str, pos: DVec2, target: DVec2) -> EvoidResult<()> {
+	let table = Script::new(key)?.table()?;
+	let fun: Function = table.get("on_attack")?;
+
+	Ok(fun.call((table, LuaDVec2(pos), LuaDVec2(target)))?)
+}
+
+/// Gets the index of the next unlocked weapon, wrapping around to the start
+pub fn swap_weapons(current_weapon: usize, weapons: &[WeaponInfo]) -> usize {
+	(1..=weapons.len())
+		.map(|offset| (current_weapon + offset) % weapons.len())
+		.find(|index| weapons[*index].unlocked)
+		.unwrap_or(current_weapon)
+}
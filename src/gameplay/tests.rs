@@ -0,0 +1,652 @@
+use std::{
+	num::NonZeroU32,
+	ops::{Deref, DerefMut},
+	sync::Arc,
+};
+
+use macroquad::math::DVec2;
+use parking_lot::{Mutex, MutexGuard};
+use stecs::prelude::*;
+
+use crate::{
+	cores::{enemytype::get_enemytypes, itemtype::get_itemtypes, weapontype::WeaponSlot},
+	data::{replay::Replay, save::Save},
+	utils::{
+		resources::{create_resources, maps::access_map, scripts::lua},
+		set_headless,
+	},
+};
+
+use super::{
+	Gameplay,
+	breakable::{Broken, is_broken},
+	combat::{
+		Attack, AttackType, Owner,
+		pattern::{Pattern, PatternShape},
+		script::AttackScript,
+	},
+	ecs::obj::{Obj, hitbox::Hitbox},
+	enemy::Enemy,
+	input::{Action, Input},
+	paused::Paused,
+	pickup::Pickup,
+	player::MAX_ENERGY,
+	simulate,
+};
+
+/// Some gameplay state, such as the input and the random seed, is global,
+/// so only one test can run gameplay at once for it to be deterministic
+static HEADLESS: Mutex<()> = Mutex::new(());
+
+/// Gameplay that holds onto the lock on running gameplay until it is dropped
+struct Headless {
+	gameplay: Gameplay,
+	_lock: MutexGuard<'static, ()>,
+}
+
+impl Deref for Headless {
+	type Target = Gameplay;
+
+	fn deref(&self) -> &Gameplay {
+		&self.gameplay
+	}
+}
+
+impl DerefMut for Headless {
+	fn deref_mut(&mut self) -> &mut Gameplay {
+		&mut self.gameplay
+	}
+}
+
+/// Creates headless gameplay within the map, which runs for the provided amount of ticks with no input
+fn headless(map: &str, ticks: usize) -> Headless {
+	play_replay(Replay {
+		// A set seed keeps the simulation the same each time it is run
+		save: Save {
+			seed: 0,
+			..Default::default()
+		},
+		start_map: map.into(),
+		inputs: vec![Input::default(); ticks],
+	})
+}
+
+/// Creates headless gameplay that plays back the replay
+fn play_replay(replay: Replay) -> Headless {
+	let lock = HEADLESS.lock();
+	set_headless();
+	create_resources();
+
+	Headless {
+		gameplay: Gameplay::from_replay(replay),
+		_lock: lock,
+	}
+}
+
+#[test]
+fn enemy_damages_player() {
+	// Long enough for the enemy to attack, but not long enough for the player to die and respawn
+	let mut gameplay = headless("default:test", 100);
+	gameplay.world.enemies.insert(Enemy::from_type(
+		&get_enemytypes()["default:test"],
+		&DVec2::new(60., 40.),
+	));
+
+	simulate(&mut gameplay);
+
+	let health = &gameplay.world.player.health[0];
+	assert!(health.hp < health.max);
+}
+
+#[test]
+fn attack_script_splits_on_expire() {
+	let mut gameplay = headless("default:test", 600);
+	let mut atk = Attack::new_projectile(
+		Obj::new(DVec2::ZERO, DVec2::X, 10.),
+		10.,
+		Owner::Player,
+		"default:attacks/projectile-player",
+	);
+	atk.script = Some(AttackScript::new("default:attacks/split").unwrap());
+	gameplay.world.attacks.insert(atk);
+
+	let mut most_attacks = 0;
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+		most_attacks = most_attacks.max(gameplay.world.attacks.ids().count());
+	}
+
+	assert_eq!(most_attacks, 3);
+}
+
+#[test]
+fn returning_projectile_is_caught() {
+	let mut gameplay = headless("default:test", 100);
+	let mut atk = Attack::new_projectile(
+		Obj::new(DVec2::ZERO, DVec2::X, 5.),
+		10.,
+		Owner::Player,
+		"default:attacks/projectile-player",
+	);
+	atk.projectile.returns = Some(60.);
+	gameplay.world.attacks.insert(atk);
+
+	let mut furthest: f64 = 0.;
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+		for obj in query!(gameplay.world.attacks, (&obj)) {
+			furthest = furthest.max(obj.pos.length());
+		}
+	}
+
+	assert!(furthest >= 60.);
+	assert_eq!(gameplay.world.attacks.ids().count(), 0);
+}
+
+#[test]
+fn piercing_projectile_hits_each_target_once() {
+	let mut gameplay = headless("default:test", 60);
+	let pos = DVec2::new(200., 200.);
+	gameplay
+		.world
+		.enemies
+		.insert(Enemy::from_type(&get_enemytypes()["default:test"], &pos));
+	let id = gameplay.world.enemies.ids().last().unwrap();
+	gameplay.world.enemies.health[id].hp = 100.;
+
+	// A slow, large projectile stays over the enemy for longer than its invincibility frames
+	let mut atk = Attack::new_projectile(
+		Obj::new(pos, pos + DVec2::X, 200.),
+		10.,
+		Owner::Player,
+		"default:attacks/projectile-player",
+	);
+	atk.obj.speed = 0.01;
+	atk.projectile.pierce = 5;
+	gameplay.world.attacks.insert(atk);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+
+	assert!((gameplay.world.enemies.health[id].hp - 90.).abs() < f64::EPSILON);
+	assert_eq!(gameplay.world.attacks.projectile[0].pierce, 4);
+}
+
+#[test]
+fn pattern_staggers_attacks() {
+	let mut gameplay = headless("default:test", 30);
+	let atk = Attack::new_projectile(
+		Obj::new(DVec2::ZERO, DVec2::X, 5.),
+		10.,
+		Owner::Player,
+		"default:attacks/projectile-player",
+	);
+	let pattern = Pattern {
+		shape: PatternShape::Ring,
+		count: NonZeroU32::new(4).unwrap(),
+		speed_variance: 0.,
+		stagger: 5.,
+	};
+	gameplay.world.queued_attacks.extend(pattern.emit(&atk));
+
+	gameplay.tick();
+	assert_eq!(gameplay.world.attacks.ids().count(), 1);
+	assert_eq!(gameplay.world.queued_attacks.len(), 3);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+	assert!(gameplay.world.queued_attacks.is_empty());
+}
+
+#[test]
+fn shotgun_pellets_all_hit_at_point_blank() {
+	let mut gameplay = headless("default:test", 10);
+	let pos = gameplay.world.player.obj[0].pos;
+	gameplay.world.enemies.insert(Enemy::from_type(
+		&get_enemytypes()["default:test"],
+		&(pos + DVec2::X * 5.),
+	));
+	let id = gameplay.world.enemies.ids().last().unwrap();
+	gameplay.world.enemies.health[id].hp = 100.;
+
+	let player = &mut gameplay.world.player;
+	let inventory = &mut player.inventory[0];
+	inventory.current_gun = inventory
+		.guns
+		.iter()
+		.position(|info| info.weapon == "default:shotgun")
+		.unwrap();
+	let attacks = inventory
+		.attack(WeaponSlot::Gun, pos, &mut player.temperature[0])
+		.unwrap();
+	let total: f64 = attacks.iter().map(|(_, atk)| atk.damage).sum();
+	assert_eq!(attacks.len(), 5);
+	gameplay.world.queued_attacks.extend(attacks);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+
+	let dealt = 100. - gameplay.world.enemies.health[id].hp;
+	assert!((dealt - total).abs() < 1e-9);
+}
+
+#[test]
+fn empty_pattern_is_rejected() {
+	assert!(ron::from_str::<Pattern>("(shape: Ring, count: 0)").is_err());
+	assert!(ron::from_str::<Pattern>("(shape: Ring, count: 1)").is_ok());
+}
+
+#[test]
+fn sword_combo_advances_then_resets() {
+	let mut gameplay = headless("default:test", 1);
+	let player = &mut gameplay.world.player;
+	let inventory = &mut player.inventory[0];
+	let temperature = &mut player.temperature[0];
+
+	let mut sizes = Vec::new();
+	for reset in [false, false, false, true] {
+		let info = &mut inventory.swords[inventory.current_sword];
+		info.cooldown = 0.;
+		if reset {
+			info.combo_window = 0.;
+		}
+
+		let attacks = inventory
+			.attack(WeaponSlot::Sword, DVec2::ZERO, temperature)
+			.unwrap();
+		sizes.push(attacks[0].1.obj.size);
+	}
+
+	assert_eq!(sizes, [36., 40., 48., 36.]);
+}
+
+#[test]
+fn guns_need_energy() {
+	let mut gameplay = headless("default:test", 1);
+	let player = &mut gameplay.world.player;
+	let inventory = &mut player.inventory[0];
+	let temperature = &mut player.temperature[0];
+
+	inventory.energy = 0.;
+	assert!(
+		inventory
+			.attack(WeaponSlot::Gun, DVec2::ZERO, temperature)
+			.is_none()
+	);
+
+	inventory.gain_energy(1000.);
+	assert!(
+		inventory
+			.attack(WeaponSlot::Gun, DVec2::ZERO, temperature)
+			.is_some()
+	);
+	assert!(inventory.energy < MAX_ENERGY);
+}
+
+#[test]
+fn weapon_pickup_unlocks_locked_weapon() {
+	let mut gameplay = headless("default:test", 1);
+	let is_unlocked = |gameplay: &Gameplay| {
+		gameplay.world.player.inventory[0]
+			.guns
+			.iter()
+			.any(|info| info.weapon == "default:radio_cannon" && info.unlocked)
+	};
+	assert!(!is_unlocked(&gameplay));
+
+	let pos = gameplay.world.player.obj[0].pos;
+	gameplay.world.pickups.insert(Pickup::from_type(
+		&get_itemtypes()["default:radio_cannon"],
+		&pos,
+		"test".into(),
+	));
+	gameplay.tick();
+
+	assert!(is_unlocked(&gameplay));
+	assert!(
+		gameplay
+			.save
+			.unlocked_weapons
+			.contains("default:radio_cannon")
+	);
+}
+
+#[test]
+fn dead_enemy_drops_items_then_despawns() {
+	let mut gameplay = headless("default:test", 100);
+	gameplay.world.enemies.insert(Enemy::from_type(
+		&get_enemytypes()["default:test"],
+		&DVec2::new(200., 200.),
+	));
+	let pickups = gameplay.world.pickups.ids().count();
+
+	let id = gameplay.world.enemies.ids().last().unwrap();
+	gameplay.world.enemies.health[id].hp = 0.;
+	gameplay.tick();
+
+	assert_eq!(gameplay.world.enemies.ids().count(), 0);
+	assert_eq!(gameplay.world.corpses.ids().count(), 1);
+	assert!(gameplay.world.pickups.ids().count() > pickups);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+	assert_eq!(gameplay.world.corpses.ids().count(), 0);
+}
+
+#[test]
+fn hits_create_damage_numbers() {
+	let mut gameplay = headless("default:test", 2);
+	let pos = DVec2::new(200., 200.);
+	gameplay
+		.world
+		.enemies
+		.insert(Enemy::from_type(&get_enemytypes()["default:test"], &pos));
+	gameplay.world.attacks.insert(Attack::new_physical(
+		Obj::new(pos, pos, 20.),
+		10.,
+		Owner::Player,
+		"default:attacks/slash",
+	));
+
+	gameplay.tick();
+
+	assert!(gameplay.feedback.events.is_empty());
+	assert_eq!(gameplay.feedback.numbers.len(), 1);
+}
+
+#[test]
+fn status_effect_damages_then_expires() {
+	let mut gameplay = headless("default:test", 200);
+	gameplay.world.player.effects[0].apply("default:burn");
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+
+	let health = &gameplay.world.player.health[0];
+	assert!(health.hp < health.max);
+	assert!(gameplay.world.player.effects[0].active.is_empty());
+}
+
+#[test]
+fn overheating_stuns_player() {
+	let mut gameplay = headless("default:test", 2);
+	gameplay.world.player.temperature[0].heat(1000.);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+
+	assert!(gameplay.world.player.obj[0].stunned > 0.);
+	assert!(gameplay.world.player.temperature[0].current < 100.);
+}
+
+#[test]
+fn arc_hitbox_only_hits_in_front() {
+	let arc = Hitbox::Arc(std::f64::consts::FRAC_PI_2);
+	let atk = Obj::new(DVec2::ZERO, DVec2::new(1., 0.), 30.);
+
+	let in_front = Obj::new(DVec2::new(35., 0.), DVec2::ZERO, 10.);
+	let behind = Obj::new(DVec2::new(-35., 0.), DVec2::ZERO, 10.);
+
+	assert!(arc.overlaps(&atk, &Hitbox::Circle, &in_front));
+	assert!(!arc.overlaps(&atk, &Hitbox::Circle, &behind));
+	assert!(Hitbox::Circle.overlaps(&atk, &Hitbox::Circle, &behind));
+}
+
+#[test]
+fn parried_projectile_hits_along_redirected_line() {
+	let mut gameplay = headless("default:test", 10);
+	let parry_pos = DVec2::new(300., 0.);
+	let enemy_pos = DVec2::new(150., 0.);
+	gameplay.world.enemies.insert(Enemy::from_type(
+		&get_enemytypes()["default:test"],
+		&enemy_pos,
+	));
+	let id = gameplay.world.enemies.ids().last().unwrap();
+	// Enough health to survive the beam, so that the damage can be checked
+	gameplay.world.enemies.health[id].max = 1000.;
+	gameplay.world.enemies.health[id].hp = 1000.;
+
+	gameplay.world.attacks.insert(Attack::new_physical(
+		Obj::new(parry_pos, parry_pos, 20.),
+		10.,
+		Owner::Player,
+		"default:attacks/slash",
+	));
+	gameplay.world.attacks.insert(Attack::new_projectile(
+		Obj::new(parry_pos, parry_pos + DVec2::X, 5.),
+		10.,
+		Owner::Enemy,
+		"default:attacks/projectile-enemy",
+	));
+
+	// With no mouse input, the redirected beam is aimed back towards the origin, passing through the enemy
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+	}
+
+	let health = &gameplay.world.enemies.health[id];
+	assert!(health.hp < health.max);
+}
+
+/// Places a sword slash of the provided age on top of a projectile, then runs a tick so that it can parry.
+/// Returns the projectile, or None if it was removed.
+fn parry_projectile(
+	gameplay: &mut Gameplay,
+	sword_owner: Owner,
+	projectile_owner: Owner,
+	age: f64,
+) -> Option<Attack> {
+	let pos = DVec2::new(300., 0.);
+
+	let mut sword = Attack::new_physical(
+		Obj::new(pos, pos, 20.),
+		10.,
+		sword_owner,
+		"default:attacks/slash",
+	);
+	sword.age = age;
+	gameplay.world.attacks.insert(sword);
+	let projectile = gameplay.world.attacks.insert(Attack::new_projectile(
+		Obj::new(pos, pos + DVec2::X, 5.),
+		10.,
+		projectile_owner,
+		"default:attacks/projectile-enemy",
+	));
+
+	gameplay.tick();
+	gameplay.world.attacks.remove(projectile)
+}
+
+#[test]
+fn sword_parries_projectile() {
+	let mut gameplay = headless("default:test", 1);
+	gameplay.world.player.inventory[0].energy = 0.;
+
+	let atk = parry_projectile(&mut gameplay, Owner::Player, Owner::Enemy, 0.).unwrap();
+
+	assert!(atk.is_parried);
+	assert!(atk.owner == Owner::Player);
+	// The sword's damage is added to the projectile's, as the damage bonus is 1
+	assert!((atk.damage - 20.).abs() < f64::EPSILON);
+	assert!(atk.atk_type == AttackType::Hitscan);
+	assert!(matches!(atk.hitbox, Hitbox::Capsule));
+	// Redirected towards the mouse, which is at the origin with no input
+	assert_eq!(atk.obj.target, DVec2::ZERO);
+	assert!((gameplay.world.player.inventory[0].energy - 20.).abs() < f64::EPSILON);
+}
+
+#[test]
+fn parry_window_expires() {
+	let mut gameplay = headless("default:test", 1);
+
+	let atk = parry_projectile(&mut gameplay, Owner::Player, Owner::Enemy, 20.).unwrap();
+
+	assert!(!atk.is_parried);
+	assert!(atk.owner == Owner::Enemy);
+	assert!(atk.atk_type == AttackType::Projectile);
+}
+
+#[test]
+fn parrying_own_attacks_needs_same_owner() {
+	let mut gameplay = headless("default:test", 1);
+	gameplay.world.player.inventory[0].energy = 0.;
+
+	// Projectiles can be parried by the same owner, but no energy is gained from it
+	let atk = parry_projectile(&mut gameplay, Owner::Player, Owner::Player, 0.).unwrap();
+	assert!(atk.is_parried);
+	assert!(gameplay.world.player.inventory[0].energy.abs() < f64::EPSILON);
+
+	// Physical attacks can't be
+	drop(gameplay);
+	let mut gameplay = headless("default:test", 1);
+	let pos = DVec2::new(300., 0.);
+	let mut ids = Vec::new();
+	for _ in 0..2 {
+		ids.push(gameplay.world.attacks.insert(Attack::new_physical(
+			Obj::new(pos, pos, 20.),
+			10.,
+			Owner::Player,
+			"default:attacks/slash",
+		)));
+	}
+	gameplay.tick();
+	for id in ids {
+		assert!(!gameplay.world.attacks.is_parried[id]);
+	}
+}
+
+#[test]
+fn arena_ignores_enemies_it_did_not_spawn() {
+	// The player starts on the arena's trigger, alongside the enemies already in the map
+	let mut gameplay = headless("default:test2", 300);
+
+	while gameplay.tick() {
+		gameplay.paused = Paused::None;
+
+		let enemies = &mut gameplay.world.enemies;
+		for (health, in_arena) in query!(enemies, (&mut health, &in_arena)) {
+			if *in_arena {
+				health.hp = 0.;
+			} else {
+				// Keeping the player alive, so that the arena isn't left by respawning
+				health.hp = health.max;
+			}
+		}
+		let player = &mut gameplay.world.player.health[0];
+		player.hp = player.max;
+	}
+
+	assert!(gameplay.world.enemies.ids().count() > 0);
+	assert!(
+		gameplay
+			.save
+			.completed_arenas
+			.contains("default:test2/test")
+	);
+}
+
+#[test]
+fn path_goes_around_wall() {
+	let _gameplay = headless("default:test", 0);
+	let map = access_map("default:test");
+	let (from, to) = (DVec2::new(0., -50.), DVec2::new(-90., -50.));
+
+	let path = map.nav.find_path(from, to, 10.).unwrap();
+
+	// The wall in the middle of the map is between the points, so the path needs to turn around it
+	assert!(path.len() > 1);
+	assert_eq!(path.last(), Some(&to));
+
+	// Paths only go around walls, so the map's breakable is treated as destroyed
+	let mut broken = Broken::default();
+	broken
+		.entry("default:test".to_owned())
+		.or_default()
+		.insert(0);
+	let mut prev = from;
+	for point in path {
+		assert!(
+			map.grid
+				.raycast(prev, point, "default:test", &broken)
+				.is_none()
+		);
+		prev = point;
+	}
+}
+
+#[test]
+fn destroyed_breakables_are_shared_with_lua() {
+	let mut gameplay = headless("default:test", 1);
+	let id = gameplay.world.breakables.ids().next().unwrap();
+	gameplay.world.breakables.health[id].hp = 0.;
+	gameplay.tick();
+
+	assert!(is_broken(&gameplay.save.broken, "default:test", 0));
+	let lua = lua();
+	let shared = lua.app_data_ref::<Arc<Broken>>().unwrap();
+	assert!(Arc::ptr_eq(&shared, &gameplay.save.broken));
+}
+
+/// The position and health of the player and enemies, alongside the position and damage of attacks
+fn snapshot(gameplay: &Gameplay) -> Vec<(DVec2, f64)> {
+	let world = &gameplay.world;
+
+	query!([world.player, world.enemies], (&obj, &health))
+		.map(|(obj, health)| (obj.pos, health.hp))
+		.chain(query!(world.attacks, (&obj, &damage)).map(|(obj, damage)| (obj.pos, *damage)))
+		.collect()
+}
+
+#[test]
+fn replay_file_plays_back_the_same() {
+	// Walking right while attacking, dashing, then walking up
+	let inputs: Vec<Input> = (0..120)
+		.map(|tick| {
+			let held: &[Action] = match tick {
+				0..40 => &[Action::Right],
+				60..100 => &[Action::Up],
+				_ => &[],
+			};
+			let pressed: &[Action] = match tick {
+				10 | 30 => &[Action::Sword],
+				20 => &[Action::Dash],
+				50 => &[Action::Gun],
+				_ => &[],
+			};
+			Input::scripted(held, pressed, DVec2::new(100., 20.))
+		})
+		.collect();
+	let replay = || Replay {
+		save: Save {
+			seed: 0,
+			..Default::default()
+		},
+		start_map: "default:test2".into(),
+		inputs: inputs.clone(),
+	};
+
+	let mut gameplay = play_replay(replay());
+	simulate(&mut gameplay);
+	let recorded = snapshot(&gameplay);
+	drop(gameplay);
+
+	let dir = std::env::temp_dir().join(format!("ethervoid-replay-{}", std::process::id()));
+	replay().save(&dir);
+	let loaded = Replay::read(&dir).unwrap();
+	_ = std::fs::remove_file(&dir);
+	assert_eq!(loaded.inputs.len(), inputs.len());
+
+	let mut gameplay = play_replay(loaded);
+	simulate(&mut gameplay);
+
+	// The player should have actually moved, rather than the session doing nothing
+	assert_ne!(gameplay.world.player.obj[0].pos, DVec2::ZERO);
+	assert!(gameplay.world.enemies.ids().count() > 0);
+	assert_eq!(snapshot(&gameplay), recorded);
+}
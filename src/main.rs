@@ -4,10 +4,11 @@ use crate::utils::resources::{
 };
 
 use self::prelude::*;
-use gameplay::{SAVE_DIR, gameplay};
+use data::replay::Replay;
+use gameplay::{Gameplay, SAVE_DIR, gameplay, simulate};
 
 use menu::{init_ui, main::menu};
-use utils::{error::EvoidResult, logger::init_log};
+use utils::{args::Args, error::EvoidResult, logger::init_log, set_headless};
 
 mod cores;
 mod data;
@@ -25,12 +26,43 @@ pub enum State {
 	Quit,
 }
 
-#[macroquad::main("Ethervoid")]
-async fn main() -> EvoidResult<()> {
-	init_log().await?;
+fn main() -> EvoidResult<()> {
+	init_log()?;
+
+	let args = Args::parse();
+	let replay = args.replay.as_ref().and_then(Replay::read);
+
+	// Headless mode has no window, so it runs before one would be created
+	if args.headless {
+		let Some(replay) = replay else {
+			error!("Headless mode requires a replay to play");
+			return Ok(());
+		};
+
+		set_headless();
+		create_resources();
+		update_config(read_config());
+		simulate(&mut Gameplay::from_replay(replay));
+		return Ok(());
+	}
+
+	macroquad::Window::new("Ethervoid", async move {
+		if let Err(e) = run(args, replay).await {
+			error!("{e}");
+		}
+	});
+	Ok(())
+}
+
+async fn run(args: Args, mut replay: Option<Replay>) -> EvoidResult<()> {
 	init_ui().await?;
 
-	let mut state = State::Menu;
+	// Replays skip the menu, and are played as soon as the game starts
+	let mut state = if replay.is_some() {
+		State::Gameplay
+	} else {
+		State::Menu
+	};
 
 	loop {
 		// Locates and creates all the resources in the game (textures, maps, etc.)
@@ -40,7 +72,13 @@ async fn main() -> EvoidResult<()> {
 
 		state = match state {
 			State::Menu => menu().await,
-			State::Gameplay => gameplay(SAVE_DIR).await,
+			State::Gameplay => {
+				gameplay(match replay.take() {
+					Some(replay) => Gameplay::from_replay(replay),
+					None => Gameplay::new(SAVE_DIR, args.record.as_deref()),
+				})
+				.await
+			}
 			State::Quit => return Ok(()),
 		};
 
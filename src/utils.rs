@@ -1,7 +1,8 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use macroquad::prelude::*;
 
+pub mod args;
 pub mod error;
 pub mod logger;
 pub mod lua;
@@ -28,6 +29,8 @@ impl AtomicF64 {
 	}
 }
 
+// Stores whether the game is running without a window or audio device.
+static HEADLESS: AtomicBool = AtomicBool::new(false);
 // Stores the delta time of the given frame.
 static DELTA_TIME: AtomicF64 = AtomicF64::new();
 // Stores the mouse position of the given frame.
@@ -60,6 +63,16 @@ pub fn update_screen_size() {
 	SCREEN_SIZE.1.set(screen_height() as f64);
 }
 
+/// Marks the game as running without a window or audio device
+pub fn set_headless() {
+	HEADLESS.store(true, Ordering::Relaxed);
+}
+
+/// Checks if the game is running without a window or audio device
+pub fn is_headless() -> bool {
+	HEADLESS.load(Ordering::Relaxed)
+}
+
 /// Gets the delta time
 pub fn delta_time() -> f64 {
 	DELTA_TIME.get()
@@ -84,11 +97,26 @@ pub fn screen_size() -> DVec2 {
 	dvec2(SCREEN_SIZE.0.get(), SCREEN_SIZE.1.get())
 }
 
+/// The amount that [`smart_time`] multiplies delta time by
+const SMART_TIME_MULT: f64 = 100. * (2. / 3.);
+
+/// How many times per second the gameplay simulation is updated
+pub const TICKS_PER_SECOND: f64 = 60.;
+
+/// The length of a single simulation tick, in seconds
+pub const TICK_LENGTH: f64 = 1. / TICKS_PER_SECOND;
+
+/// The amount of gameplay time that passes within a single simulation tick.
+///
+/// This uses the same scale as [`smart_time`], so that existing cooldowns and speeds stay the same.
+pub const TICK: f64 = TICK_LENGTH * SMART_TIME_MULT;
+
 /// Gets the delta time and multiplies it to approximately equal `1` at 60 FPS
+///
+/// This varies with the framerate, so it should only be used for rendering.
+/// Gameplay should use [`TICK`] instead.
 pub fn smart_time() -> f64 {
-	const MULT: f64 = 100. * (2. / 3.);
-
-	delta_time() * MULT
+	delta_time() * SMART_TIME_MULT
 }
 
 /// Gets the scale that the camera should be rendered at
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use tracing::warn;
+
+/// The options that the game was launched with
+#[derive(Default)]
+pub struct Args {
+	/// Records the input of each gameplay session to this file
+	pub record: Option<PathBuf>,
+	/// Plays back the replay within this file, instead of starting at the menu
+	pub replay: Option<PathBuf>,
+	/// Plays back the replay as fast as possible, without rendering it
+	pub headless: bool,
+}
+
+impl Args {
+	/// Reads the options from the command line
+	pub fn parse() -> Self {
+		let mut args = Self::default();
+		let mut iter = std::env::args().skip(1);
+
+		while let Some(arg) = iter.next() {
+			match arg.as_str() {
+				"--record" => args.record = iter.next().map(PathBuf::from),
+				"--replay" => args.replay = iter.next().map(PathBuf::from),
+				"--headless" => args.headless = true,
+				_ => warn!("Unknown argument: {arg}"),
+			}
+		}
+
+		args
+	}
+}
@@ -40,7 +40,7 @@ impl Write for Logger {
 }
 
 /// Initiates the logger. Should do nothing if already called.
-pub async fn init_log() -> EvoidResult<()> {
+pub fn init_log() -> EvoidResult<()> {
 	// Renaming old log
 	_ = std::fs::rename("./output.log", "./output.log.old");
 
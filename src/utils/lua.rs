@@ -4,6 +4,8 @@ use macroquad::prelude::*;
 use mlua::{FromLua, IntoLua, Lua, Value};
 use tracing::error;
 
+use crate::cores::map::grid::RaycastHit;
+
 mod instance;
 
 pub struct LuaDVec2(pub DVec2);
@@ -52,6 +54,17 @@ impl From<Vec2> for LuaDVec2 {
 	}
 }
 
+impl IntoLua for RaycastHit {
+	fn into_lua(self, lua: &Lua) -> mlua::Result<Value> {
+		let table = lua.create_table()?;
+
+		table.set("pos", LuaDVec2(self.pos))?;
+		table.set("normal", LuaDVec2(self.normal))?;
+
+		Ok(Value::Table(table))
+	}
+}
+
 pub fn create_lua() -> Lua {
 	match instance::try_create_lua() {
 		Ok(ok) => ok,
@@ -1,16 +1,26 @@
-use mlua::{Lua, Number, Table, Value, Variadic};
+use std::sync::Arc;
+
+use macroquad::math::DVec2;
+use mlua::{Lua, LuaSerdeExt, Number, Table, Value, Variadic};
 use tracing::{debug, error, info, trace, warn};
 
 use crate::{
+	cores::map::grid::RaycastHit,
 	gameplay::{
-		combat::{Attack, Owner},
+		breakable::Broken,
+		combat::{Attack, Owner, pattern::Pattern, projectile::Projectile, script::AttackScript},
 		ecs::obj::Obj,
+		input::input,
 	},
 	utils::{
-		angle_between, delta_time,
+		TICK_LENGTH, angle_between,
 		error::EvoidResult,
-		mouse_pos, mouse_pos_local,
-		resources::{audio::play_random_sound, scripts::access_script},
+		resources::{
+			audio::play_random_sound,
+			effects::access_effect,
+			maps::{access_map, current_map},
+			scripts::access_script,
+		},
 	},
 };
 
@@ -68,38 +78,85 @@ fn lua_attack_fns(lua: &Lua) -> EvoidResult<()> {
 
 	attacks.set(
 		"physical",
-		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String| {
-			Attack::new_physical(Obj::new(*pos, *target, size), damage, Owner::Enemy, &key)
+		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String, owner: Option<String>, damage_type: _| {
+			lua_attack(Attack::new_physical, Obj::new(*pos, *target, size), damage, &key, owner.as_deref(), damage_type)
 		}),
 	)?;
 	attacks.set(
 		"burst",
-		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, key: String| {
-			Attack::new_burst(Obj::new(*pos, *pos, size), damage, Owner::Enemy, &key)
+		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, key: String, owner: Option<String>, damage_type: _| {
+			lua_attack(Attack::new_burst, Obj::new(*pos, *pos, size), damage, &key, owner.as_deref(), damage_type)
 		}),
 	)?;
 	attacks.set(
 		"projectile",
-		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String| {
-			Attack::new_projectile(Obj::new(*pos, *target, size), damage, Owner::Enemy, &key)
+		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String, owner: Option<String>, damage_type: _, projectile: Option<Projectile>| {
+			let mut atk = lua_attack(Attack::new_projectile, Obj::new(*pos, *target, size), damage, &key, owner.as_deref(), damage_type);
+			atk.projectile = projectile.unwrap_or_default();
+			atk
 		}),
 	)?;
 	attacks.set(
 		"hitscan",
-		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String| {
-			Attack::new_hitscan(Obj::new(*pos, *target, size), damage, Owner::Enemy, &key)
+		lua_fn!(lua, |damage: _, size: _, pos: LuaDVec2, target: LuaDVec2, key: String, owner: Option<String>, damage_type: _| {
+			lua_attack(Attack::new_hitscan, Obj::new(*pos, *target, size), damage, &key, owner.as_deref(), damage_type)
 		}),
 	)?;
 
 	attacks.set("_attacks", lua.create_table()?)?;
-	attacks.set("spawn", lua.create_function(|lua, atk: Attack| {
+	attacks.set("_queued", lua.create_table()?)?;
+	// Attacks can optionally be given a script, which controls them after they are spawned
+	attacks.set("spawn", lua.create_function(|lua, (mut atk, script): (Attack, Option<String>)| {
+		if let Some(key) = script {
+			atk.script = Some(AttackScript::new(&key).map_err(mlua::Error::external)?);
+		}
+
 		lua.globals().get::<Table>("attack")?.get::<Table>("_attacks")?.push(atk)
 	})?)?;
 
+	// Spawns copies of the attack in a pattern, with each being given its own copy of the script
+	attacks.set("pattern", lua.create_function(|lua, (mut atk, pattern, script): (Attack, Pattern, Option<String>)| {
+		if let Some(key) = script {
+			atk.script = Some(AttackScript::new(&key).map_err(mlua::Error::external)?);
+		}
+
+		let queued = lua.globals().get::<Table>("attack")?.get::<Table>("_queued")?;
+		for (delay, atk) in pattern.emit(&atk) {
+			queued.push(lua.create_sequence_from([Value::Number(delay), Value::UserData(lua.create_userdata(atk)?)])?)?;
+		}
+
+		Ok(())
+	})?)?;
+
 	lua.globals().set("attack", attacks)?;
 	Ok(())
 }
 
+/// Creates an attack from Lua.
+///
+/// These belong to enemies unless said to belong to the player, and deal the damage type
+/// of their [`AttackType`](crate::gameplay::combat::AttackType) unless given another.
+fn lua_attack(
+	new: fn(Obj, f64, Owner, &str) -> Attack,
+	obj: Obj,
+	damage: f64,
+	key: &str,
+	owner: Option<&str>,
+	damage_type: Option<String>,
+) -> Attack {
+	let owner = match owner {
+		Some("player") => Owner::Player,
+		_ => Owner::Enemy,
+	};
+
+	let mut atk = new(obj, damage, owner, key);
+	if let Some(damage_type) = damage_type {
+		atk.damage_type = damage_type;
+	}
+
+	atk
+}
+
 fn lua_math_fns(lua: &Lua) -> EvoidResult<()> {
 	let math: Table = lua.globals().get("math")?;
 
@@ -173,11 +230,47 @@ fn lua_log_fns(lua: &Lua) -> EvoidResult<()> {
 fn lua_engine_fns(lua: &Lua) -> EvoidResult<()> {
 	let engine = lua.create_table()?;
 
-	engine.set("delta_time", lua_fn!(lua, delta_time))?;
-	engine.set("mouse_pos", lua_fn!(lua, || LuaDVec2::from(mouse_pos())))?;
+	// Scripts are run once per simulation tick, so they always see the length of a tick
+	engine.set("delta_time", lua_fn!(lua, || TICK_LENGTH))?;
+	engine.set(
+		"mouse_pos",
+		lua_fn!(lua, || LuaDVec2::from(input().mouse_pos())),
+	)?;
 	engine.set(
 		"mouse_pos_local",
-		lua_fn!(lua, || LuaDVec2::from(mouse_pos_local())),
+		lua_fn!(lua, || LuaDVec2::from(input().mouse_pos_local())),
+	)?;
+
+	// Both of these only check against the barriers of the current map, and ignore entities
+	engine.set(
+		"raycast",
+		lua.create_function(|lua, (from, to): (LuaDVec2, LuaDVec2)| Ok(raycast(lua, *from, *to)))?,
+	)?;
+	engine.set(
+		"has_line_of_sight",
+		lua.create_function(|lua, (from, to): (LuaDVec2, LuaDVec2)| {
+			Ok(raycast(lua, *from, *to).is_none())
+		})?,
+	)?;
+
+	// Paths are found around walls only, so breakables and entities still need to be moved around
+	engine.set(
+		"find_path",
+		lua_fn!(lua, |from: LuaDVec2, to: LuaDVec2, radius: f64| {
+			access_map(&current_map())
+				.nav
+				.find_path(*from, *to, radius)
+				.map(|path| path.into_iter().map(LuaDVec2).collect::<Vec<_>>())
+		}),
+	)?;
+
+	engine.set(
+		"effect",
+		lua.create_function(|lua, key: String| {
+			access_effect(&key)
+				.map(|effect| lua.to_value(effect))
+				.transpose()
+		})?,
 	)?;
 
 	engine.set(
@@ -192,6 +285,19 @@ fn lua_engine_fns(lua: &Lua) -> EvoidResult<()> {
 	Ok(())
 }
 
+/// Casts a ray through the current map, seeing through the breakables that gameplay has shared as destroyed
+fn raycast(lua: &Lua, from: DVec2, to: DVec2) -> Option<RaycastHit> {
+	let current_map = current_map();
+	let broken = lua
+		.app_data_ref::<Arc<Broken>>()
+		.map(|broken| Arc::clone(&broken))
+		.unwrap_or_default();
+
+	access_map(&current_map)
+		.grid
+		.raycast(from, to, &current_map, &broken)
+}
+
 fn stringify_args(args: Variadic<Value>) -> String {
 	args.into_iter()
 		.map(|value| match value {
@@ -1,23 +1,35 @@
 use std::sync::LazyLock;
 
 use audio::create_sounds;
+use damage::create_damage_types;
+use effects::create_effects;
+use items::create_items;
 use rustc_hash::FxHashMap;
 
 use maps::create_maps;
+use parries::create_parries;
 use scripts::create_script_vals;
+use temperatures::create_temperatures;
 use tracing::info;
 
 use parking_lot::{RwLock, RwLockReadGuard};
 use textures::create_textures;
+use weapons::create_weapons;
 
 use crate::{cores::cores_changed, utils::resources::langs::create_langs};
 
 pub mod audio;
 pub mod config;
+pub mod damage;
+pub mod effects;
+pub mod items;
 pub mod langs;
 pub mod maps;
+pub mod parries;
 pub mod scripts;
+pub mod temperatures;
 pub mod textures;
+pub mod weapons;
 
 // This module contains globally available resources
 // Everyone always says "don't do this" so fuck you I did
@@ -61,6 +73,12 @@ pub fn create_resources() {
 		s.spawn(|_| create_sounds());
 		s.spawn(|_| create_script_vals());
 		s.spawn(|_| create_langs());
+		s.spawn(|_| create_weapons());
+		s.spawn(|_| create_damage_types());
+		s.spawn(|_| create_effects());
+		s.spawn(|_| create_temperatures());
+		s.spawn(|_| create_parries());
+		s.spawn(|_| create_items());
 	});
 	create_maps(); // Maps depend on the existance of the other resources
 	info!("All resources loaded!");
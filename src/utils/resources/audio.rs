@@ -8,7 +8,10 @@ use kira::{
 
 use crate::{
 	cores::audio::get_audio,
-	utils::resources::{Global, global},
+	utils::{
+		is_headless,
+		resources::{Global, global},
+	},
 };
 
 use super::{Resource, resource, set_resource};
@@ -33,6 +36,9 @@ pub fn play_sound(key: &str) -> Option<StaticSoundHandle> {
 		error!("Sound {key} not found");
 		return None;
 	};
+	if is_headless() {
+		return None;
+	}
 
 	match MANAGER.write().play(sound.clone()) {
 		Ok(ok) => Some(ok),
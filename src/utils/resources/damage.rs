@@ -0,0 +1,26 @@
+use tracing::error;
+
+use crate::cores::damagetype::{DamageType, get_damagetypes};
+
+use super::{Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Damage types
+ */
+
+static DAMAGE_TYPES: Resource<DamageType> = resource();
+
+/// Populates the damage type `HashMap`
+pub(super) fn create_damage_types() {
+	set_resource(&DAMAGE_TYPES, get_damagetypes());
+}
+
+/// Gets the damage type at the provided key
+pub fn access_damage_type(key: &str) -> Option<&'static DamageType> {
+	let damage_type = get_resource_ref(&DAMAGE_TYPES, key);
+	if damage_type.is_none() {
+		error!("Damage type {key} not found");
+	}
+
+	damage_type
+}
@@ -0,0 +1,26 @@
+use tracing::error;
+
+use crate::cores::effecttype::{EffectType, get_effecttypes};
+
+use super::{Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Status effects
+ */
+
+static EFFECTS: Resource<EffectType> = resource();
+
+/// Populates the effect `HashMap`
+pub(super) fn create_effects() {
+	set_resource(&EFFECTS, get_effecttypes());
+}
+
+/// Gets the effect at the provided key
+pub fn access_effect(key: &str) -> Option<&'static EffectType> {
+	let effect = get_resource_ref(&EFFECTS, key);
+	if effect.is_none() {
+		error!("Effect {key} not found");
+	}
+
+	effect
+}
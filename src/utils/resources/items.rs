@@ -0,0 +1,26 @@
+use tracing::error;
+
+use crate::cores::itemtype::{ItemType, get_itemtypes};
+
+use super::{Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Items
+ */
+
+static ITEMS: Resource<ItemType> = resource();
+
+/// Populates the item `HashMap`
+pub(super) fn create_items() {
+	set_resource(&ITEMS, get_itemtypes());
+}
+
+/// Gets the item at the provided key
+pub fn access_item(key: &str) -> Option<&'static ItemType> {
+	let item = get_resource_ref(&ITEMS, key);
+	if item.is_none() {
+		error!("Item {key} not found");
+	}
+
+	item
+}
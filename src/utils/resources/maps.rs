@@ -1,13 +1,12 @@
-use std::sync::LazyLock;
+use std::sync::{LazyLock, OnceLock};
 
 use tracing::error;
 
-use crate::{
-	cores::map::{Map, get_maps},
-	gameplay::draw::process::to_texture,
-};
+use crate::cores::map::{Map, get_maps, grid::BarrierGrid, nav::NavGrid};
 
-use super::{Resource, get_resource_ref, resource, set_resource, textures::access_image};
+use super::{
+	Global, Resource, get_resource_ref, global, resource, set_resource, textures::access_image,
+};
 
 /*
  * Maps
@@ -15,6 +14,8 @@ use super::{Resource, get_resource_ref, resource, set_resource, textures::access
 
 static ERR_MAP: LazyLock<Map> = LazyLock::new(init_err_map);
 static MAPS: Resource<Map> = resource();
+/// The key of the map that gameplay is currently within
+static CURRENT_MAP: Global<String> = global!(String::new());
 
 /// Populates the map `HashMap`
 pub(super) fn create_maps() {
@@ -31,12 +32,30 @@ pub fn access_map(key: &str) -> &Map {
 	}
 }
 
+/// Sets the map that gameplay is currently within
+pub fn set_current_map(key: &str) {
+	*CURRENT_MAP.write() = key.into();
+}
+
+/// Gets the key of the map that gameplay is currently within.
+///
+/// This is for code without access to the current gameplay, such as Lua functions.
+pub fn current_map() -> String {
+	CURRENT_MAP.read().clone()
+}
+
 fn init_err_map() -> Map {
 	Map {
 		walls: [].into(),
 		doors: [].into(),
+		breakables: [].into(),
 		enemies: [].into(),
 		npcs: [].into(),
-		texture: to_texture(access_image("")),
+		pickups: [].into(),
+		arenas: [].into(),
+		grid: BarrierGrid::default(),
+		nav: NavGrid::default(),
+		image: access_image("").clone(),
+		texture: OnceLock::new(),
 	}
 }
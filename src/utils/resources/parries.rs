@@ -0,0 +1,26 @@
+use tracing::error;
+
+use crate::cores::parrytype::{ParryType, get_parrytypes};
+
+use super::{Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Parries
+ */
+
+static PARRIES: Resource<ParryType> = resource();
+
+/// Populates the parry `HashMap`
+pub(super) fn create_parries() {
+	set_resource(&PARRIES, get_parrytypes());
+}
+
+/// Gets the parry at the provided key
+pub fn access_parry(key: &str) -> Option<&'static ParryType> {
+	let parry = get_resource_ref(&PARRIES, key);
+	if parry.is_none() {
+		error!("Parry {key} not found");
+	}
+
+	parry
+}
@@ -0,0 +1,26 @@
+use tracing::error;
+
+use crate::cores::temperaturetype::{TemperatureType, get_temperaturetypes};
+
+use super::{Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Temperatures
+ */
+
+static TEMPERATURES: Resource<TemperatureType> = resource();
+
+/// Populates the temperature `HashMap`
+pub(super) fn create_temperatures() {
+	set_resource(&TEMPERATURES, get_temperaturetypes());
+}
+
+/// Gets the temperature stats at the provided key
+pub fn access_temperature(key: &str) -> Option<&'static TemperatureType> {
+	let temperature = get_resource_ref(&TEMPERATURES, key);
+	if temperature.is_none() {
+		error!("Temperature {key} not found");
+	}
+
+	temperature
+}
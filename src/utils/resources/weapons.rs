@@ -0,0 +1,31 @@
+use tracing::error;
+
+use crate::cores::weapontype::{WeaponType, get_weapontypes};
+
+use super::{GlobalAccess, Resource, get_resource_ref, resource, set_resource};
+
+/*
+ * Weapons
+ */
+
+static WEAPONS: Resource<WeaponType> = resource();
+
+/// Populates the weapon `HashMap`
+pub(super) fn create_weapons() {
+	set_resource(&WEAPONS, get_weapontypes());
+}
+
+/// Gets the weapon at the provided key
+pub fn access_weapon(key: &str) -> Option<&'static WeaponType> {
+	let weapon = get_resource_ref(&WEAPONS, key);
+	if weapon.is_none() {
+		error!("Weapon {key} not found");
+	}
+
+	weapon
+}
+
+/// Gets access to every weapon
+pub fn access_weapons() -> GlobalAccess<rustc_hash::FxHashMap<String, WeaponType>> {
+	WEAPONS.read()
+}